@@ -0,0 +1,122 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A structure standing on the board cells it occupies.
+#[derive(Component, Debug, Clone)]
+pub struct Building {
+    pub kind: String,
+    pub cells: Vec<(usize, usize)>,
+}
+
+#[derive(Component, Debug)]
+pub struct UnderConstruction {
+    pub timer: Timer,
+}
+
+/// A unit waiting in a production queue, with what was paid for it so
+/// cancelling refunds exactly that.
+#[derive(Debug, Clone)]
+pub struct QueuedUnit {
+    pub unit: String,
+    pub cost: f32,
+    pub timer: Timer,
+}
+
+/// Units a finished building is producing, front first. Only the front one's
+/// timer runs.
+#[derive(Component, Debug, Default)]
+pub struct ProductionQueue {
+    pub items: VecDeque<QueuedUnit>,
+}
+
+/// Where a building sends the units it produces.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct RallyPoint(pub Vec3);
+
+/// A unit type a building can produce, and what it takes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductionOption {
+    pub unit: String,
+    pub cost: f32,
+    /// Seconds from the front of the queue until the unit is out.
+    pub build_time: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildingDefinition {
+    pub cost: f32,
+    /// Seconds from placement until the building is finished.
+    pub build_time: f32,
+    pub health: f32,
+    /// Width and depth in board cells.
+    pub footprint: (usize, usize),
+    pub tooltip: String,
+    /// Whether harvesters can unload here once it is finished.
+    #[serde(default)]
+    pub depot: bool,
+    /// Unit types it can produce once finished.
+    #[serde(default)]
+    pub produces: Vec<ProductionOption>,
+    /// How many units can garrison inside once finished.
+    #[serde(default)]
+    pub garrison: usize,
+}
+
+/// Every building type, loaded from the buildings file.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildingDefinitions {
+    pub buildings: HashMap<String, BuildingDefinition>,
+}
+
+impl BuildingDefinitions {
+    pub fn from_ron(source: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    pub fn load_or_default(path: &str) -> Self {
+        match fs::read_to_string(path).map(|source| Self::from_ron(&source)) {
+            Ok(Ok(definitions)) => definitions,
+            Ok(Err(error)) => {
+                warn!("Could not parse {}: {}. No buildings defined.", path, error);
+                Self::default()
+            }
+            Err(error) => {
+                warn!("Could not read {}: {}. No buildings defined.", path, error);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn get(&self, kind: &str) -> Option<&BuildingDefinition> {
+        self.buildings.get(kind)
+    }
+
+    pub fn production(&self, kind: &str, unit: &str) -> Option<&ProductionOption> {
+        self.get(kind)?
+            .produces
+            .iter()
+            .find(|option| option.unit == unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BuildingDefinitions;
+
+    #[test]
+    fn buildings_file_parses() {
+        let definitions =
+            BuildingDefinitions::from_ron(include_str!("../../assets/data/buildings.ron")).unwrap();
+
+        assert!(definitions.get("depot").unwrap().depot);
+        assert!(!definitions.get("shipyard").unwrap().depot);
+        assert!(definitions.get("depot").unwrap().garrison > 0);
+        assert!(definitions.production("shipyard", "ship").is_some());
+        assert!(definitions.production("depot", "ship").is_none());
+    }
+}
@@ -0,0 +1,131 @@
+use std::{collections::HashMap, fs};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::mechanics::Team;
+
+/// A patch of resources that harvesters gather from until it runs out.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct ResourceNode {
+    pub remaining: f32,
+}
+
+/// Where harvesters of the depot's team unload their cargo.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct Depot;
+
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct Harvester {
+    /// Resources gathered per second.
+    pub gather_rate: f32,
+    pub capacity: f32,
+    pub cargo: f32,
+    /// Heading back to a depot, either full or because the node ran out.
+    pub returning: bool,
+}
+
+/// Resources banked by each team.
+#[derive(Resource, Debug, Default)]
+pub struct Stockpiles(HashMap<Team, f32>);
+
+impl Stockpiles {
+    pub fn get(&self, team: Team) -> f32 {
+        self.0.get(&team).copied().unwrap_or_default()
+    }
+
+    pub fn add(&mut self, team: Team, amount: f32) {
+        *self.0.entry(team).or_default() += amount;
+    }
+
+    /// Takes `cost` from the team's stockpile if it can afford it.
+    pub fn try_spend(&mut self, team: Team, cost: f32) -> bool {
+        if self.get(team) < cost {
+            return false;
+        }
+        self.add(team, -cost);
+        true
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeDefinition {
+    pub x: f32,
+    pub z: f32,
+    pub amount: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepotDefinition {
+    pub team: u8,
+    pub x: f32,
+    pub z: f32,
+}
+
+/// Resource nodes, depots and gathering rules, loaded from the economy file.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EconomyConfig {
+    pub starting_resources: f32,
+    pub gather_range: f32,
+    pub depot_range: f32,
+    pub search_radius: f32,
+    pub nodes: Vec<NodeDefinition>,
+    pub depots: Vec<DepotDefinition>,
+}
+
+impl EconomyConfig {
+    pub fn from_ron(source: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    pub fn load_or_default(path: &str) -> Self {
+        match fs::read_to_string(path).map(|source| Self::from_ron(&source)) {
+            Ok(Ok(config)) => config,
+            Ok(Err(error)) => {
+                warn!(
+                    "Could not parse {}: {}. The map has no resources.",
+                    path, error
+                );
+                Self::default()
+            }
+            Err(error) => {
+                warn!(
+                    "Could not read {}: {}. The map has no resources.",
+                    path, error
+                );
+                Self::default()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EconomyConfig, Stockpiles};
+    use crate::components::mechanics::Team;
+
+    #[test]
+    fn economy_file_parses() {
+        let config =
+            EconomyConfig::from_ron(include_str!("../../assets/data/economy.ron")).unwrap();
+
+        assert!(!config.nodes.is_empty());
+        assert!(config
+            .depots
+            .iter()
+            .any(|depot| depot.team == Team::PLAYER.0));
+    }
+
+    #[test]
+    fn spending_more_than_the_stockpile_fails() {
+        let mut stockpiles = Stockpiles::default();
+        stockpiles.add(Team(1), 50.);
+
+        assert!(!stockpiles.try_spend(Team(1), 60.));
+        assert!(stockpiles.try_spend(Team(1), 50.));
+        assert_eq!(stockpiles.get(Team(1)), 0.);
+    }
+}
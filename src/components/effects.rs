@@ -1,24 +1,277 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
-#[derive(Component, Reflect, Default, Clone)]
-#[reflect(Component)]
-pub struct Blinker {
-    pub timer: Timer,
-    pub speed: f32,
-    pub duration: f32,
-    pub number_of_blinks: usize,
-    pub duration_const: f32,
-    pub direction: f32,
-}
-
-impl Blinker {
-    pub fn new(speed: f32, duration: f32, number_of_blinks: usize) -> Self {
+
+/// How a tween's progress through a pass maps onto its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    SineInOut,
+}
+
+impl Easing {
+    /// Eases `t`, clamped to 0..=1. Every curve starts at 0 and ends at 1.
+    pub fn ease(&self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => t * (2. - t),
+            Easing::QuadInOut if t < 0.5 => 2. * t * t,
+            Easing::QuadInOut => 1. - (-2. * t + 2.).powi(2) / 2.,
+            Easing::SineInOut => (1. - (t * std::f32::consts::PI).cos()) / 2.,
+        }
+    }
+}
+
+/// What a tween animates. Material properties are set on the entity's own
+/// material instances, see `EffectMaterials`; scale on its `Transform`.
+/// `Glow` is emissive from what each material had before it was instanced, so
+/// flashes on models that already glow come back to their glow.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TweenProperty {
+    Alpha { from: f32, to: f32 },
+    Emissive { from: Color, to: Color },
+    Glow { to: Color },
+    Color { from: Color, to: Color },
+    Scale { from: Vec3, to: Vec3 },
+}
+
+impl TweenProperty {
+    pub fn uses_material(&self) -> bool {
+        !matches!(self, TweenProperty::Scale { .. })
+    }
+}
+
+pub fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let [r1, g1, b1, a1] = from.as_rgba_f32();
+    let [r2, g2, b2, a2] = to.as_rgba_f32();
+    let mix = |a: f32, b: f32| a + (b - a) * t;
+    Color::rgba(mix(r1, r2), mix(g1, g2), mix(b1, b2), mix(a1, a2))
+}
+
+/// How many times a tween plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TweenMode {
+    Once,
+    Times(u32),
+    /// Never finishes.
+    Loop,
+}
+
+/// Animates one property from `from` to `to` over `duration` seconds a pass.
+/// Ping-pong tweens come back to `from` on a second pass before counting as
+/// played once.
+#[derive(Debug, Clone)]
+pub struct Tween {
+    /// Names it in `TweenCompleted`. A new tween with the same label replaces
+    /// it.
+    pub label: String,
+    pub property: TweenProperty,
+    pub easing: Easing,
+    pub mode: TweenMode,
+    pub ping_pong: bool,
+    timer: Timer,
+    passes: u32,
+}
+
+impl Tween {
+    /// Plays once, linearly.
+    pub fn new(label: &str, property: TweenProperty, duration: f32) -> Self {
         Self {
-            timer: Timer::from_seconds(0.01, TimerMode::Repeating),
-            speed: speed,
-            duration: duration,
-            number_of_blinks: number_of_blinks,
-            duration_const: duration,
-            direction: -1.,
+            label: label.to_string(),
+            property,
+            easing: Easing::Linear,
+            mode: TweenMode::Once,
+            ping_pong: false,
+            timer: Timer::from_seconds(duration, TimerMode::Repeating),
+            passes: 0,
+        }
+    }
+
+    /// Fades out and back in `blinks` times, `period` seconds a blink.
+    pub fn blink(label: &str, period: f32, blinks: u32) -> Self {
+        Self::new(
+            label,
+            TweenProperty::Alpha { from: 1., to: 0. },
+            period / 2.,
+        )
+        .with_easing(Easing::SineInOut)
+        .with_mode(TweenMode::Times(blinks))
+        .ping_pong()
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn with_mode(mut self, mode: TweenMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn ping_pong(mut self) -> Self {
+        self.ping_pong = true;
+        self
+    }
+
+    fn passes_to_finish(&self) -> Option<u32> {
+        let passes_per_play = if self.ping_pong { 2 } else { 1 };
+        match self.mode {
+            TweenMode::Once => Some(passes_per_play),
+            TweenMode::Times(times) => Some(times * passes_per_play),
+            TweenMode::Loop => None,
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.passes_to_finish()
+            .map_or(false, |passes| self.passes >= passes)
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        if self.finished() {
+            return;
+        }
+        self.passes += self.timer.tick(delta).times_finished_this_tick();
+    }
+
+    /// How far from `from` toward `to` it is, eased. Finished tweens rest at
+    /// `to`, or at `from` if they ping-pong.
+    pub fn progress(&self) -> f32 {
+        if self.finished() {
+            return if self.ping_pong { 0. } else { 1. };
         }
+        let t = self.timer.percent();
+        let t = if self.ping_pong && self.passes % 2 == 1 {
+            1. - t
+        } else {
+            t
+        };
+        self.easing.ease(t)
+    }
+}
+
+/// The tweens playing on an entity.
+#[derive(Component, Debug, Default, Clone)]
+pub struct Tweens {
+    pub tweens: Vec<Tween>,
+}
+
+impl Tweens {
+    pub fn new(tween: Tween) -> Self {
+        Self {
+            tweens: vec![tween],
+        }
+    }
+
+    pub fn with(mut self, tween: Tween) -> Self {
+        self.add(tween);
+        self
+    }
+
+    pub fn add(&mut self, tween: Tween) {
+        match self
+            .tweens
+            .iter_mut()
+            .find(|other| other.label == tween.label)
+        {
+            Some(other) => *other = tween,
+            None => self.tweens.push(tween),
+        }
+    }
+
+    pub fn uses_material(&self) -> bool {
+        self.tweens
+            .iter()
+            .any(|tween| tween.property.uses_material())
+    }
+}
+
+/// Material instances owned by one entity and its descendants, so tweening
+/// them leaves every other user of the original materials alone. Added the
+/// first time the entity has a material tween.
+#[derive(Component, Debug, Default, Clone)]
+pub struct EffectMaterials {
+    pub materials: Vec<Handle<StandardMaterial>>,
+    /// The emissive of each material when it was instanced, for `Glow`.
+    pub emissive: Vec<Color>,
+}
+
+/// Sent when a tween on `entity` finishes. The entity is left alone.
+pub struct TweenCompleted {
+    pub entity: Entity,
+    pub label: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::prelude::Vec3;
+
+    use super::{Easing, Tween, TweenMode, TweenProperty, Tweens};
+
+    fn scale(duration: f32) -> Tween {
+        Tween::new(
+            "scale",
+            TweenProperty::Scale {
+                from: Vec3::ONE,
+                to: Vec3::splat(2.),
+            },
+            duration,
+        )
+    }
+
+    #[test]
+    fn easings_start_at_zero_and_end_at_one() {
+        for easing in [
+            Easing::Linear,
+            Easing::QuadIn,
+            Easing::QuadOut,
+            Easing::QuadInOut,
+            Easing::SineInOut,
+        ] {
+            assert!(easing.ease(0.).abs() < 1e-6);
+            assert!((easing.ease(1.) - 1.).abs() < 1e-6);
+        }
+        assert!(Easing::QuadIn.ease(0.5) < 0.5 && Easing::QuadOut.ease(0.5) > 0.5);
+    }
+
+    #[test]
+    fn ping_pong_comes_back_before_finishing() {
+        let mut tween = scale(1.).ping_pong().with_mode(TweenMode::Times(2));
+
+        tween.tick(Duration::from_secs_f32(1.25));
+        assert!((tween.progress() - 0.75).abs() < 1e-4);
+        tween.tick(Duration::from_secs_f32(2.));
+        assert!(!tween.finished());
+        tween.tick(Duration::from_secs_f32(1.));
+        assert!(tween.finished());
+        assert_eq!(tween.progress(), 0.);
+    }
+
+    #[test]
+    fn looping_tweens_never_finish() {
+        let mut tween = scale(0.5).with_mode(TweenMode::Loop);
+
+        tween.tick(Duration::from_secs(60));
+
+        assert!(!tween.finished());
+    }
+
+    #[test]
+    fn tweens_with_the_same_label_replace_each_other() {
+        let mut tweens = Tweens::new(scale(1.));
+        tweens.add(scale(2.));
+        tweens.add(Tween::blink("blink", 0.5, 3));
+
+        assert_eq!(tweens.tweens.len(), 2);
+        assert!(tweens.uses_material());
     }
 }
@@ -1,5 +1,142 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::mechanics::Direction,
+    constants::{constants::GROUND_LEVEL, plane::CELL_SIZE},
+    systems::spawn_plane::Cell,
+};
+
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
 pub enum GameState {
+    // NotPlaying,
     Playing,
     GameOver,
 }
+
+#[derive(Resource, Default)]
+pub struct Game {
+    pub board: Vec<Vec<Cell>>,
+    pub mechanics: Mechanics,
+}
+
+/// Why a footprint of cells cannot be built on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementError {
+    OutOfBounds,
+    Occupied,
+    Uneven,
+}
+
+impl Game {
+    /// The `(i, j)` cell under `position`; `board` is indexed `[j][i]`.
+    pub fn cell_index(&self, position: Vec3) -> Option<(usize, usize)> {
+        if position.x < 0. || position.z < 0. {
+            return None;
+        }
+        let (i, j) = (
+            (position.x / CELL_SIZE) as usize,
+            (position.z / CELL_SIZE) as usize,
+        );
+        self.board.get(j)?.get(i)?;
+        Some((i, j))
+    }
+
+    pub fn cell(&self, (i, j): (usize, usize)) -> Option<&Cell> {
+        self.board.get(j)?.get(i)
+    }
+
+    pub fn cell_mut(&mut self, (i, j): (usize, usize)) -> Option<&mut Cell> {
+        self.board.get_mut(j)?.get_mut(i)
+    }
+
+    /// Every cell of a `size` footprint whose lowest corner is `origin`.
+    pub fn footprint(origin: (usize, usize), size: (usize, usize)) -> Vec<(usize, usize)> {
+        (origin.1..origin.1 + size.1)
+            .flat_map(|j| (origin.0..origin.0 + size.0).map(move |i| (i, j)))
+            .collect()
+    }
+
+    /// The middle of a footprint, at ground level.
+    pub fn footprint_center(origin: (usize, usize), size: (usize, usize)) -> Vec3 {
+        Vec3::new(
+            (origin.0 as f32 + size.0 as f32 / 2.) * CELL_SIZE,
+            GROUND_LEVEL,
+            (origin.1 as f32 + size.1 as f32 / 2.) * CELL_SIZE,
+        )
+    }
+
+    /// A footprint can be built on if it lies on the board, nothing stands on
+    /// it and all its cells are the same height.
+    pub fn check_placement(
+        &self,
+        origin: (usize, usize),
+        size: (usize, usize),
+    ) -> Result<(), PlacementError> {
+        let mut height = None;
+        for index in Self::footprint(origin, size) {
+            let cell = self.cell(index).ok_or(PlacementError::OutOfBounds)?;
+            if cell.occupant.is_some() || cell.blocked {
+                return Err(PlacementError::Occupied);
+            }
+            if *height.get_or_insert(cell.height) != cell.height {
+                return Err(PlacementError::Uneven);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct Mechanics {
+    pub move_cooldown: Timer,
+    pub rotate_cooldown: Timer,
+    pub direction: Direction,
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{Game, PlacementError};
+    use crate::{constants::plane::CELL_SIZE, systems::spawn_plane::Cell};
+
+    fn board(width: usize, depth: usize) -> Game {
+        Game {
+            board: vec![vec![Cell::default(); width]; depth],
+            ..default()
+        }
+    }
+
+    #[test]
+    fn positions_map_to_the_cell_under_them() {
+        let game = board(3, 3);
+
+        assert_eq!(
+            game.cell_index(Vec3::new(CELL_SIZE * 1.5, 0., CELL_SIZE * 2.5)),
+            Some((1, 2))
+        );
+        assert_eq!(game.cell_index(Vec3::new(-1., 0., 1.)), None);
+        assert_eq!(game.cell_index(Vec3::new(CELL_SIZE * 3.5, 0., 1.)), None);
+    }
+
+    #[test]
+    fn placement_needs_free_flat_cells_on_the_board() {
+        let mut game = board(3, 3);
+        game.cell_mut((1, 0)).unwrap().occupant = Some(Entity::from_raw(7));
+        game.cell_mut((2, 2)).unwrap().height = 1.;
+
+        assert_eq!(game.check_placement((0, 1), (2, 1)), Ok(()));
+        assert_eq!(
+            game.check_placement((0, 0), (2, 1)),
+            Err(PlacementError::Occupied)
+        );
+        assert_eq!(
+            game.check_placement((1, 2), (2, 1)),
+            Err(PlacementError::Uneven)
+        );
+        assert_eq!(
+            game.check_placement((2, 0), (2, 1)),
+            Err(PlacementError::OutOfBounds)
+        );
+    }
+}
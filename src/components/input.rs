@@ -0,0 +1,520 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::orders::OrderKind;
+
+/// What the player wants to do, independent of the device used to ask for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    CameraForward,
+    CameraBack,
+    CameraLeft,
+    CameraRight,
+    CameraRotateLeft,
+    CameraRotateRight,
+    CameraDrag,
+    Select,
+    /// Clicking a selection panel icon with this held selects every selected
+    /// unit of its type.
+    SelectAllOfType,
+    Command,
+    Restart,
+    SpawnUnits,
+    AssignControlGroup(u8),
+    AddToControlGroup(u8),
+    RecallControlGroup(u8),
+    CommandCard(CardKey),
+}
+
+/// A command card hotkey: an order, the nth of the card's other buttons
+/// (abilities, production, research and buildings) in the order they appear,
+/// or cancelling production.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CardKey {
+    Order(OrderKind),
+    Slot(u8),
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputKind {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+}
+
+// Index is the control group number.
+const NUMBER_KEYS: [KeyCode; 10] = [
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+// Index is the command card slot.
+const CARD_SLOT_KEYS: [KeyCode; 6] = [
+    KeyCode::Z,
+    KeyCode::C,
+    KeyCode::V,
+    KeyCode::B,
+    KeyCode::N,
+    KeyCode::T,
+];
+const CARD_SLOT_BUTTONS: [GamepadButtonType; 4] = [
+    GamepadButtonType::South,
+    GamepadButtonType::East,
+    GamepadButtonType::West,
+    GamepadButtonType::North,
+];
+
+/// Modifier keys that must be held, e.g. `Ctrl` for `Ctrl+1`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        shift: false,
+        ctrl: false,
+        alt: false,
+    };
+
+    pub fn held(keyboard: &Input<KeyCode>) -> Self {
+        Self {
+            shift: keyboard.any_pressed([KeyCode::LShift, KeyCode::RShift]),
+            ctrl: keyboard.any_pressed([KeyCode::LControl, KeyCode::RControl]),
+            alt: keyboard.any_pressed([KeyCode::LAlt, KeyCode::RAlt]),
+        }
+    }
+}
+
+pub fn is_modifier_key(key: KeyCode) -> bool {
+    matches!(
+        key,
+        KeyCode::LShift
+            | KeyCode::RShift
+            | KeyCode::LControl
+            | KeyCode::RControl
+            | KeyCode::LAlt
+            | KeyCode::RAlt
+    )
+}
+
+/// When a binding fires, so one input can mean different things depending on
+/// whether the player has units selected.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BindingContext {
+    #[default]
+    Always,
+    UnitsSelected,
+    NothingSelected,
+}
+
+impl BindingContext {
+    pub fn applies(self, units_selected: bool) -> bool {
+        match self {
+            BindingContext::Always => true,
+            BindingContext::UnitsSelected => units_selected,
+            BindingContext::NothingSelected => !units_selected,
+        }
+    }
+
+    /// Whether both can apply at once.
+    pub fn overlaps(self, other: BindingContext) -> bool {
+        [true, false]
+            .into_iter()
+            .any(|units_selected| self.applies(units_selected) && other.applies(units_selected))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Binding {
+    pub input: InputKind,
+    #[serde(default)]
+    pub modifiers: Modifiers,
+    #[serde(default)]
+    pub context: BindingContext,
+}
+
+impl Binding {
+    pub const fn new(input: InputKind) -> Self {
+        Self::with(input, Modifiers::NONE)
+    }
+
+    pub const fn with(input: InputKind, modifiers: Modifiers) -> Self {
+        Self {
+            input,
+            modifiers,
+            context: BindingContext::Always,
+        }
+    }
+
+    pub const fn when(self, context: BindingContext) -> Self {
+        Self { context, ..self }
+    }
+}
+
+/// Every input that triggers each action. Loaded from the bindings file and
+/// editable at runtime through `RebindRequest`.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputBindings {
+    pub bindings: HashMap<Action, Vec<Binding>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use Action::*;
+        use CardKey::*;
+        use InputKind::*;
+
+        let mut bindings: HashMap<Action, Vec<Binding>> = [
+            (
+                CameraForward,
+                vec![Key(KeyCode::Up), Gamepad(GamepadButtonType::DPadUp)],
+            ),
+            (
+                CameraBack,
+                vec![Key(KeyCode::Down), Gamepad(GamepadButtonType::DPadDown)],
+            ),
+            (
+                CameraLeft,
+                vec![Key(KeyCode::Left), Gamepad(GamepadButtonType::DPadLeft)],
+            ),
+            (
+                CameraRight,
+                vec![Key(KeyCode::Right), Gamepad(GamepadButtonType::DPadRight)],
+            ),
+            (
+                CameraRotateLeft,
+                vec![Key(KeyCode::Q), Gamepad(GamepadButtonType::LeftTrigger)],
+            ),
+            (
+                CameraRotateRight,
+                vec![Key(KeyCode::E), Gamepad(GamepadButtonType::RightTrigger)],
+            ),
+            (CameraDrag, vec![Mouse(MouseButton::Middle)]),
+            (Select, vec![Mouse(MouseButton::Left)]),
+            (Command, vec![Mouse(MouseButton::Right)]),
+            (
+                Restart,
+                vec![Key(KeyCode::Space), Gamepad(GamepadButtonType::Start)],
+            ),
+            (CommandCard(Order(OrderKind::Move)), vec![Key(KeyCode::M)]),
+            (CommandCard(Order(OrderKind::Stop)), vec![Key(KeyCode::X)]),
+            (CommandCard(Order(OrderKind::Hold)), vec![Key(KeyCode::H)]),
+            (CommandCard(Order(OrderKind::Patrol)), vec![Key(KeyCode::P)]),
+            (CommandCard(Order(OrderKind::Guard)), vec![Key(KeyCode::R)]),
+            (CommandCard(Order(OrderKind::Gather)), vec![Key(KeyCode::G)]),
+            (CommandCard(Order(OrderKind::Load)), vec![Key(KeyCode::L)]),
+            (CommandCard(Order(OrderKind::Unload)), vec![Key(KeyCode::U)]),
+            (
+                CommandCard(Cancel),
+                vec![Key(KeyCode::Escape), Gamepad(GamepadButtonType::Select)],
+            ),
+        ]
+        .into_iter()
+        .map(|(action, inputs)| (action, inputs.into_iter().map(Binding::new).collect()))
+        .collect();
+
+        let shift = Modifiers {
+            shift: true,
+            ..Modifiers::NONE
+        };
+        let ctrl = Modifiers {
+            ctrl: true,
+            ..Modifiers::NONE
+        };
+        // WASD pans only with nothing selected, so A is free to attack with.
+        for (action, key) in [
+            (CameraForward, KeyCode::W),
+            (CameraBack, KeyCode::S),
+            (CameraLeft, KeyCode::A),
+            (CameraRight, KeyCode::D),
+        ] {
+            bindings
+                .get_mut(&action)
+                .expect("every camera direction is bound above")
+                .push(Binding::new(Key(key)).when(BindingContext::NothingSelected));
+        }
+        bindings.insert(
+            CommandCard(Order(OrderKind::Attack)),
+            vec![Binding::new(Key(KeyCode::A)).when(BindingContext::UnitsSelected)],
+        );
+
+        bindings.insert(
+            SelectAllOfType,
+            vec![Binding::with(Mouse(MouseButton::Left), ctrl)],
+        );
+        // Apart from `Command`, so right-clicks with the spawner on still move.
+        bindings.insert(
+            SpawnUnits,
+            vec![Binding::with(Mouse(MouseButton::Right), shift)],
+        );
+
+        for (group, key) in NUMBER_KEYS.into_iter().enumerate() {
+            let group = group as u8;
+            bindings.insert(RecallControlGroup(group), vec![Binding::new(Key(key))]);
+            bindings.insert(
+                AddToControlGroup(group),
+                vec![Binding::with(Key(key), shift)],
+            );
+            bindings.insert(
+                AssignControlGroup(group),
+                vec![Binding::with(Key(key), ctrl)],
+            );
+        }
+
+        for (slot, key) in CARD_SLOT_KEYS.into_iter().enumerate() {
+            let mut inputs = vec![Binding::new(Key(key))];
+            if let Some(button) = CARD_SLOT_BUTTONS.get(slot) {
+                inputs.push(Binding::new(Gamepad(*button)));
+            }
+            bindings.insert(CommandCard(Slot(slot as u8)), inputs);
+        }
+
+        Self { bindings }
+    }
+}
+
+impl InputBindings {
+    pub fn from_ron(source: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    pub fn load_or_default(path: &str) -> Self {
+        match fs::read_to_string(path).map(|source| Self::from_ron(&source)) {
+            Ok(Ok(bindings)) => bindings,
+            Ok(Err(error)) => {
+                warn!(
+                    "Could not parse {}: {}. Using default bindings.",
+                    path, error
+                );
+                Self::default()
+            }
+            Err(error) => {
+                warn!(
+                    "Could not read {}: {}. Using default bindings.",
+                    path, error
+                );
+                Self::default()
+            }
+        }
+    }
+
+    /// Replaces the bindings of every action the file at `path` binds. A
+    /// missing file leaves them as they are.
+    pub fn with_overrides(mut self, path: &str) -> Self {
+        let Ok(source) = fs::read_to_string(path) else {
+            return self;
+        };
+        match Self::from_ron(&source) {
+            Ok(overrides) => self.bindings.extend(overrides.bindings),
+            Err(error) => warn!("Could not parse {}: {}. Ignoring it.", path, error),
+        }
+        self
+    }
+
+    /// The actions bound differently than in `defaults`.
+    pub fn overrides(&self, defaults: &InputBindings) -> Self {
+        Self {
+            bindings: self
+                .bindings
+                .iter()
+                .filter(|(action, bindings)| defaults.bindings.get(action) != Some(bindings))
+                .map(|(action, bindings)| (*action, bindings.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn save(&self, path: &str) {
+        let source = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .expect("bindings are always serializable");
+        if let Err(error) = fs::write(path, source) {
+            warn!("Could not save bindings to {}: {}", path, error);
+        }
+    }
+
+    /// Replaces every binding of `action` with `binding`.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        self.bindings.insert(action, vec![binding]);
+    }
+}
+
+/// Per-frame state of every action, computed from `InputBindings`. Gameplay
+/// systems read this instead of raw key codes.
+#[derive(Resource, Debug, Default)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    just_released: HashSet<Action>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        self.just_released.contains(&action)
+    }
+
+    /// Replaces the set of held actions, deriving the just pressed/released sets
+    /// from the previous frame so chords work whichever key goes down first.
+    pub fn update(&mut self, pressed: HashSet<Action>) {
+        self.just_pressed = pressed.difference(&self.pressed).copied().collect();
+        self.just_released = self.pressed.difference(&pressed).copied().collect();
+        self.pressed = pressed;
+    }
+}
+
+/// Asks for the next input the player presses to become the binding of `action`.
+pub struct RebindRequest {
+    pub action: Action,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct PendingRebind(pub Option<Action>);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use bevy::prelude::*;
+
+    use super::{
+        Action, ActionState, Binding, BindingContext, CardKey, InputBindings, InputKind, Modifiers,
+    };
+    use crate::components::orders::OrderKind;
+
+    #[test]
+    fn bindings_file_parses() {
+        let bindings =
+            InputBindings::from_ron(include_str!("../../assets/config/bindings.ron")).unwrap();
+
+        assert!(bindings.bindings[&Action::CameraForward].contains(
+            &Binding::new(InputKind::Key(KeyCode::W)).when(BindingContext::NothingSelected)
+        ));
+    }
+
+    #[test]
+    fn bindings_file_matches_defaults() {
+        let bindings =
+            InputBindings::from_ron(include_str!("../../assets/config/bindings.ron")).unwrap();
+
+        assert_eq!(bindings, InputBindings::default());
+    }
+
+    #[test]
+    fn no_input_is_bound_to_two_actions() {
+        let bindings =
+            InputBindings::from_ron(include_str!("../../assets/config/bindings.ron")).unwrap();
+
+        let mut bound: HashMap<(InputKind, Modifiers), Vec<(BindingContext, Action)>> =
+            HashMap::new();
+        for (action, inputs) in &bindings.bindings {
+            for binding in inputs {
+                let actions = bound.entry((binding.input, binding.modifiers)).or_default();
+                if let Some((_, other)) = actions
+                    .iter()
+                    .find(|(context, _)| context.overlaps(binding.context))
+                {
+                    panic!("{:?} is bound to {:?} and {:?}", binding, other, action);
+                }
+                actions.push((binding.context, *action));
+            }
+        }
+    }
+
+    #[test]
+    fn a_attacks_with_units_selected_and_pans_without() {
+        let bindings = InputBindings::default();
+        let a = InputKind::Key(KeyCode::A);
+        let fires = |action: Action, units_selected: bool| {
+            bindings.bindings[&action]
+                .iter()
+                .any(|binding| binding.input == a && binding.context.applies(units_selected))
+        };
+        let attack = Action::CommandCard(CardKey::Order(OrderKind::Attack));
+
+        assert!(fires(attack, true) && !fires(Action::CameraLeft, true));
+        assert!(fires(Action::CameraLeft, false) && !fires(attack, false));
+    }
+
+    #[test]
+    fn bindings_survive_a_round_trip() {
+        let mut bindings = InputBindings::default();
+        bindings.rebind(
+            Action::Restart,
+            Binding::with(
+                InputKind::Key(KeyCode::R),
+                Modifiers {
+                    ctrl: true,
+                    ..default()
+                },
+            ),
+        );
+
+        let source = ron::to_string(&bindings).unwrap();
+
+        assert_eq!(InputBindings::from_ron(&source).unwrap(), bindings);
+    }
+
+    #[test]
+    fn only_rebound_actions_are_overrides() {
+        let defaults = InputBindings::default();
+        let mut bindings = defaults.clone();
+        let binding = Binding::new(InputKind::Key(KeyCode::R));
+        bindings.rebind(Action::Restart, binding);
+
+        let overrides = bindings.overrides(&defaults);
+
+        assert_eq!(overrides.bindings.len(), 1);
+        assert_eq!(overrides.bindings[&Action::Restart], [binding]);
+    }
+
+    #[test]
+    fn held_modifiers_are_read_from_either_side() {
+        let mut keyboard = Input::<KeyCode>::default();
+        keyboard.press(KeyCode::RControl);
+        keyboard.press(KeyCode::LShift);
+
+        let modifiers = Modifiers::held(&keyboard);
+
+        assert!(modifiers.ctrl && modifiers.shift && !modifiers.alt);
+    }
+
+    #[test]
+    fn action_is_just_pressed_only_on_first_frame() {
+        let mut state = ActionState::default();
+        let held: HashSet<Action> = [Action::Restart].into_iter().collect();
+
+        state.update(held.clone());
+        assert!(state.just_pressed(Action::Restart));
+
+        state.update(held);
+        assert!(state.pressed(Action::Restart));
+        assert!(!state.just_pressed(Action::Restart));
+
+        state.update(HashSet::new());
+        assert!(state.just_released(Action::Restart));
+    }
+}
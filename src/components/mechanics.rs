@@ -1,5 +1,12 @@
+use std::{collections::HashMap, time::Duration};
+
 use bevy::prelude::*;
 
+use crate::constants::{
+    minimap::TEAM_COLORS,
+    units::{STUCK_MIN_PROGRESS, STUCK_NUDGES, STUCK_REPATHS, STUCK_TIMEOUT},
+};
+
 #[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
 pub struct Unit;
@@ -8,6 +15,84 @@ pub struct Unit;
 #[reflect(Component)]
 pub struct Destination(pub Vec3);
 
+/// Heading for a waypoint around whatever blocked the unit. It makes for
+/// `resume` again once it gets there.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct Detour {
+    pub resume: Vec3,
+}
+
+/// Watches a moving unit's progress toward its `Destination`. After `timeout`
+/// seconds without getting `min_progress` closer, the unit tries `repaths`
+/// detours, then `nudges` shoves, and then gives up on its order.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct StuckDetection {
+    pub timeout: f32,
+    pub min_progress: f32,
+    pub repaths: u8,
+    pub nudges: u8,
+    /// Where the unit was heading when last checked.
+    pub target: Vec3,
+    /// The closest it has come to `target`.
+    pub best_distance: f32,
+    /// Seconds since it last got closer.
+    pub stalled: f32,
+    /// Detours and nudges tried since the unit was given its order.
+    pub attempts: u8,
+}
+
+impl Default for StuckDetection {
+    fn default() -> Self {
+        Self::new(
+            STUCK_TIMEOUT,
+            STUCK_MIN_PROGRESS,
+            STUCK_REPATHS,
+            STUCK_NUDGES,
+        )
+    }
+}
+
+impl StuckDetection {
+    pub fn new(timeout: f32, min_progress: f32, repaths: u8, nudges: u8) -> Self {
+        Self {
+            timeout,
+            min_progress,
+            repaths,
+            nudges,
+            target: Vec3::ZERO,
+            best_distance: f32::MAX,
+            stalled: 0.,
+            attempts: 0,
+        }
+    }
+
+    /// Records that the unit is `distance` from `target`. True once it has
+    /// gone `timeout` seconds without getting closer, after which the clock
+    /// starts again.
+    pub fn track(&mut self, target: Vec3, distance: f32, delta_seconds: f32) -> bool {
+        if self.target != target || distance < self.best_distance - self.min_progress {
+            self.target = target;
+            self.best_distance = distance;
+            self.stalled = 0.;
+            return false;
+        }
+        self.stalled += delta_seconds;
+        if self.stalled < self.timeout {
+            return false;
+        }
+        self.best_distance = distance;
+        self.stalled = 0.;
+        true
+    }
+
+    /// Forgets the last order's progress and attempts.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.timeout, self.min_progress, self.repaths, self.nudges);
+    }
+}
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct Direction {
@@ -23,6 +108,12 @@ pub struct SelectionHighlighter;
 #[reflect(Component)]
 pub struct Selected;
 
+/// Which kind of unit this is, e.g. `"ship"`. Units of the same type are
+/// selected together on double-click.
+#[derive(Component, Reflect, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub struct UnitType(pub String);
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct Target {
@@ -33,6 +124,20 @@ pub struct Target {
 #[reflect(Component)]
 pub struct Health {
     pub value: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { value: max, max }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.max <= 0. {
+            return 0.;
+        }
+        (self.value / self.max).clamp(0., 1.)
+    }
 }
 
 #[derive(Component, Reflect, Default)]
@@ -47,6 +152,17 @@ pub struct MovementSpeed {
     pub value: f32,
 }
 
+/// How fast a kinematic unit moved last tick, so its neighbours can avoid where
+/// it is heading.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct KinematicVelocity(pub Vec3);
+
+/// Moved by Rapier, through its velocity, instead of by setting its transform.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct PhysicsMovement;
+
 #[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
 pub struct RotationSpeed {
@@ -56,3 +172,77 @@ pub struct RotationSpeed {
 #[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
 pub struct Rotating;
+
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub struct Team(pub u8);
+
+impl Team {
+    /// The team controlled by the local player.
+    pub const PLAYER: Team = Team(0);
+
+    pub fn color(&self) -> Color {
+        TEAM_COLORS[(self.0 as usize).min(TEAM_COLORS.len() - 1)]
+    }
+}
+
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct Weapon {
+    pub damage: f32,
+    pub range: f32,
+    pub cooldown: Timer,
+}
+
+/// Makes a unit's shots burst where they land, hurting every enemy within
+/// `radius` of the target and pushing physics bodies away.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct Splash {
+    pub radius: f32,
+    /// Share of the damage lost by the edge of the burst.
+    pub falloff: f32,
+    pub impulse: f32,
+}
+
+impl Weapon {
+    /// A weapon that is ready to fire straight away.
+    pub fn new(damage: f32, range: f32, cooldown: f32) -> Self {
+        let mut cooldown = Timer::from_seconds(cooldown, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Self {
+            damage,
+            range,
+            cooldown,
+        }
+    }
+}
+
+/// Cooldowns of the abilities a unit has used, by ability name. Abilities
+/// missing from the map are ready.
+#[derive(Component, Default, Debug)]
+pub struct AbilityCooldowns(pub HashMap<String, Timer>);
+
+impl AbilityCooldowns {
+    pub fn is_ready(&self, ability: &str) -> bool {
+        self.0.get(ability).map_or(true, |timer| timer.finished())
+    }
+
+    pub fn start(&mut self, ability: &str, seconds: f32) {
+        self.0.insert(
+            ability.to_string(),
+            Timer::from_seconds(seconds, TimerMode::Once),
+        );
+    }
+
+    /// How much of the cooldown is left, from 1 just after use to 0 when ready.
+    pub fn remaining(&self, ability: &str) -> f32 {
+        self.0.get(ability).map_or(0., |timer| 1. - timer.percent())
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        for timer in self.0.values_mut() {
+            timer.tick(delta);
+        }
+    }
+}
@@ -1,3 +1,11 @@
+pub mod buildings;
+pub mod economy;
 pub mod effects;
 pub mod game;
+pub mod input;
 pub mod mechanics;
+pub mod orders;
+pub mod research;
+pub mod stats;
+pub mod transport;
+pub mod vision;
@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// What a unit is currently doing, as shown to the player. Movement itself is
+/// driven by `Destination`; this records why the unit is moving.
+#[derive(Component, Debug, Default, Clone, PartialEq)]
+pub enum Order {
+    #[default]
+    Idle,
+    Move(Vec3),
+    /// Stay put, firing at enemies in range but never chasing them.
+    Hold,
+    Attack(Entity),
+    /// Head for `destination`, stopping to fight any enemy that comes within
+    /// the acquisition radius on the way, `engaging` one at a time.
+    AttackMove {
+        destination: Vec3,
+        engaging: Option<Entity>,
+    },
+    /// Walk from point to point, back to the first after the last, for as
+    /// long as the order stands. `next` indexes the point being walked to.
+    Patrol {
+        points: Vec<Vec3>,
+        next: usize,
+    },
+    /// Follow `ward` at `offset` from it and fight off whoever attacks it,
+    /// `engaging` one attacker at a time.
+    Guard {
+        ward: Entity,
+        offset: Vec3,
+        engaging: Option<Entity>,
+    },
+    /// Ferry resources from a node to the nearest depot until told otherwise.
+    Gather(Entity),
+    /// Carry the cargo to a point and set it down there.
+    Unload(Vec3),
+}
+
+impl Order {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Order::Idle => "Idle",
+            Order::Move(_) => "Move",
+            Order::Hold => "Hold",
+            Order::Attack(_) => "Attack",
+            Order::AttackMove { .. } => "Attack-move",
+            Order::Patrol { .. } => "Patrol",
+            Order::Guard { .. } => "Guard",
+            Order::Gather(_) => "Gather",
+            Order::Unload(_) => "Unload",
+        }
+    }
+
+    /// Moves a patrol on to its next point, back to the first after the last,
+    /// and returns that point.
+    pub fn next_patrol_point(&mut self) -> Option<Vec3> {
+        let Order::Patrol { points, next } = self else {
+            return None;
+        };
+        if points.is_empty() {
+            return None;
+        }
+        *next = (*next + 1) % points.len();
+        Some(points[*next])
+    }
+
+    /// The label with what the order is about, for the order display.
+    pub fn description(&self) -> String {
+        match self {
+            Order::Patrol { points, .. } => format!("Patrol ({} points)", points.len()),
+            Order::Guard {
+                engaging: Some(_), ..
+            }
+            | Order::AttackMove {
+                engaging: Some(_), ..
+            } => format!("{} (engaging)", self.label()),
+            _ => self.label().to_string(),
+        }
+    }
+}
+
+/// The orders on the command card, each with its own bindable hotkey.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum OrderKind {
+    Move,
+    Stop,
+    Hold,
+    Attack,
+    Patrol,
+    Guard,
+    Gather,
+    Load,
+    Unload,
+}
+
+impl OrderKind {
+    pub const ALL: [OrderKind; 9] = [
+        OrderKind::Move,
+        OrderKind::Stop,
+        OrderKind::Hold,
+        OrderKind::Attack,
+        OrderKind::Patrol,
+        OrderKind::Guard,
+        OrderKind::Gather,
+        OrderKind::Load,
+        OrderKind::Unload,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OrderKind::Move => "Move",
+            OrderKind::Stop => "Stop",
+            OrderKind::Hold => "Hold",
+            OrderKind::Attack => "Attack",
+            OrderKind::Patrol => "Patrol",
+            OrderKind::Guard => "Guard",
+            OrderKind::Gather => "Gather",
+            OrderKind::Load => "Load",
+            OrderKind::Unload => "Unload",
+        }
+    }
+
+    pub fn tooltip(&self) -> &'static str {
+        match self {
+            OrderKind::Move => "Move to a point.",
+            OrderKind::Stop => "Stop and drop the current order.",
+            OrderKind::Hold => "Hold position, firing at enemies in range without chasing them.",
+            OrderKind::Attack => {
+                "Attack a unit, chasing it until it dies, or attack-move to a point, fighting whatever comes close on the way."
+            }
+            OrderKind::Patrol => {
+                "Patrol between here and a point. Keep clicking to add more points."
+            }
+            OrderKind::Guard => "Follow a friendly unit and fight off its attackers.",
+            OrderKind::Gather => "Gather from a resource node and bring it to a depot.",
+            OrderKind::Load => "Take in the nearest friendly units around, as many as fit.",
+            OrderKind::Unload => "Set everyone inside down around a point.",
+        }
+    }
+
+    /// Whether the order waits for a click on the map before it is given.
+    pub fn needs_target(&self) -> bool {
+        match self {
+            OrderKind::Move
+            | OrderKind::Attack
+            | OrderKind::Patrol
+            | OrderKind::Guard
+            | OrderKind::Gather
+            | OrderKind::Unload => true,
+            OrderKind::Stop | OrderKind::Hold | OrderKind::Load => false,
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::{
+    mechanics::Team,
+    stats::{ModifierStack, StatModifier},
+};
+
+/// Something a building can research once for its team.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResearchDefinition {
+    /// The building type that researches it.
+    pub building: String,
+    pub cost: f32,
+    /// Seconds from starting until it is done.
+    pub research_time: f32,
+    pub tooltip: String,
+    /// Research the team must have finished first.
+    #[serde(default)]
+    pub requires: Vec<String>,
+    pub modifiers: Vec<StatModifier>,
+}
+
+/// The tech tree, loaded from the research file.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResearchDefinitions {
+    pub research: HashMap<String, ResearchDefinition>,
+}
+
+impl ResearchDefinitions {
+    pub fn from_ron(source: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    pub fn load_or_default(path: &str) -> Self {
+        match fs::read_to_string(path).map(|source| Self::from_ron(&source)) {
+            Ok(Ok(definitions)) => definitions,
+            Ok(Err(error)) => {
+                warn!("Could not parse {}: {}. No research defined.", path, error);
+                Self::default()
+            }
+            Err(error) => {
+                warn!("Could not read {}: {}. No research defined.", path, error);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ResearchDefinition> {
+        self.research.get(name)
+    }
+
+    /// Names of what a building of `kind` can research, sorted.
+    pub fn available_at(&self, kind: &str) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .research
+            .iter()
+            .filter(|(_, definition)| definition.building == kind)
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+/// What each team has researched, and the modifiers that earned it.
+#[derive(Resource, Debug, Default)]
+pub struct TeamResearch {
+    completed: HashMap<Team, HashSet<String>>,
+    modifiers: HashMap<Team, ModifierStack>,
+}
+
+impl TeamResearch {
+    pub fn is_complete(&self, team: Team, name: &str) -> bool {
+        self.completed
+            .get(&team)
+            .map_or(false, |completed| completed.contains(name))
+    }
+
+    /// Whether the team has finished everything `definition` requires.
+    pub fn can_start(&self, team: Team, definition: &ResearchDefinition) -> bool {
+        definition
+            .requires
+            .iter()
+            .all(|required| self.is_complete(team, required))
+    }
+
+    pub fn complete(&mut self, team: Team, name: &str, definition: &ResearchDefinition) {
+        if !self
+            .completed
+            .entry(team)
+            .or_default()
+            .insert(name.to_string())
+        {
+            return;
+        }
+        self.modifiers
+            .entry(team)
+            .or_default()
+            .modifiers
+            .extend(definition.modifiers.iter().cloned());
+    }
+
+    pub fn modifiers(&self, team: Team) -> ModifierStack {
+        self.modifiers.get(&team).cloned().unwrap_or_default()
+    }
+}
+
+/// Research in progress at a building.
+#[derive(Component, Debug)]
+pub struct Researching {
+    pub research: String,
+    pub timer: Timer,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ResearchDefinitions, TeamResearch};
+    use crate::components::{
+        mechanics::Team,
+        stats::{ModifierStack, StatModifier},
+    };
+
+    #[test]
+    fn research_file_parses() {
+        let definitions =
+            ResearchDefinitions::from_ron(include_str!("../../assets/data/research.ron")).unwrap();
+
+        assert!(!definitions.available_at("shipyard").is_empty());
+        assert!(definitions.available_at("depot").is_empty());
+    }
+
+    #[test]
+    fn research_requires_its_prerequisites_and_counts_once() {
+        let definitions =
+            ResearchDefinitions::from_ron(include_str!("../../assets/data/research.ron")).unwrap();
+        let (name, definition) = definitions
+            .research
+            .iter()
+            .find(|(_, definition)| !definition.requires.is_empty())
+            .unwrap();
+        let mut research = TeamResearch::default();
+
+        assert!(!research.can_start(Team::PLAYER, definition));
+        for required in &definition.requires {
+            research.complete(Team::PLAYER, required, definitions.get(required).unwrap());
+        }
+        assert!(research.can_start(Team::PLAYER, definition));
+
+        research.complete(Team::PLAYER, name, definition);
+        let modifiers = research.modifiers(Team::PLAYER).modifiers.len();
+        research.complete(Team::PLAYER, name, definition);
+        assert_eq!(research.modifiers(Team::PLAYER).modifiers.len(), modifiers);
+        assert!(!research.is_complete(Team(1), name));
+    }
+}
@@ -0,0 +1,253 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+
+/// A unit stat research and effects can change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Stat {
+    Health,
+    Speed,
+    RotationSpeed,
+    Damage,
+    Range,
+    SightRange,
+}
+
+impl Stat {
+    /// Whether timed effects can change it. Health is a pool that units lose
+    /// and regain, so only research raises it.
+    pub fn can_be_timed(self) -> bool {
+        self != Stat::Health
+    }
+}
+
+/// Reads the stat of a timed effect from a data file, refusing ones that
+/// cannot be timed.
+pub fn deserialize_timed_stat<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Stat, D::Error> {
+    let stat = Stat::deserialize(deserializer)?;
+    if !stat.can_be_timed() {
+        return Err(D::Error::custom(format!(
+            "{:?} cannot be changed by timed effects",
+            stat
+        )));
+    }
+    Ok(stat)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum StatChange {
+    Add(f32),
+    /// Percent of the value after every `Add`.
+    Percent(f32),
+    /// Times the value after every `Add` and `Percent`, as for slows.
+    Multiply(f32),
+}
+
+/// `base` plus every `Add`, scaled by the sum of every `Percent`, then by the
+/// product of every `Multiply`.
+fn combine(base: f32, changes: impl IntoIterator<Item = StatChange>) -> f32 {
+    let (mut added, mut percent, mut factor) = (0., 0., 1.);
+    for change in changes {
+        match change {
+            StatChange::Add(amount) => added += amount,
+            StatChange::Percent(amount) => percent += amount,
+            StatChange::Multiply(amount) => factor *= amount,
+        }
+    }
+    (base + added) * (1. + percent / 100.) * factor
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatModifier {
+    pub stat: Stat,
+    pub change: StatChange,
+    /// The unit types it applies to; every type if empty.
+    #[serde(default)]
+    pub unit_types: Vec<String>,
+}
+
+impl StatModifier {
+    pub fn applies_to(&self, stat: Stat, unit_type: &str) -> bool {
+        self.stat == stat
+            && (self.unit_types.is_empty() || self.unit_types.iter().any(|t| t == unit_type))
+    }
+}
+
+/// Every modifier a team has earned, applied on top of the base stats from
+/// the unit definitions.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ModifierStack {
+    pub modifiers: Vec<StatModifier>,
+}
+
+impl ModifierStack {
+    /// `base` with every modifier on `stat` for this unit type.
+    pub fn apply(&self, stat: Stat, unit_type: &str, base: f32) -> f32 {
+        combine(
+            base,
+            self.modifiers
+                .iter()
+                .filter(|modifier| modifier.applies_to(stat, unit_type))
+                .map(|modifier| modifier.change),
+        )
+    }
+}
+
+/// A timed change to one stat of one unit.
+#[derive(Debug, Clone)]
+pub struct StatEffect {
+    /// What caused it, such as an ability or an aura. Another effect from the
+    /// same source on the same stat refreshes it instead of stacking.
+    pub source: String,
+    pub stat: Stat,
+    pub change: StatChange,
+    pub timer: Timer,
+}
+
+impl StatEffect {
+    pub fn new(source: &str, stat: Stat, change: StatChange, duration: f32) -> Self {
+        Self {
+            source: source.to_string(),
+            stat,
+            change,
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+        }
+    }
+}
+
+/// The slows, hastes and auras on a unit. The unit's stat components hold its
+/// base values, research included; gameplay reads them through `effective`.
+#[derive(Component, Debug, Default, Clone)]
+pub struct StatEffects {
+    pub effects: Vec<StatEffect>,
+}
+
+impl StatEffects {
+    /// Ignores effects on stats that cannot be timed.
+    pub fn add(&mut self, effect: StatEffect) {
+        if !effect.stat.can_be_timed() {
+            warn!("Ignoring a timed effect on {:?}", effect.stat);
+            return;
+        }
+        match self
+            .effects
+            .iter_mut()
+            .find(|other| other.source == effect.source && other.stat == effect.stat)
+        {
+            Some(other) => *other = effect,
+            None => self.effects.push(effect),
+        }
+    }
+
+    /// Drops the effects that have run out.
+    pub fn tick(&mut self, delta: Duration) {
+        self.effects
+            .retain_mut(|effect| !effect.timer.tick(delta).finished());
+    }
+
+    pub fn apply(&self, stat: Stat, base: f32) -> f32 {
+        combine(
+            base,
+            self.effects
+                .iter()
+                .filter(|effect| effect.stat == stat)
+                .map(|effect| effect.change),
+        )
+    }
+}
+
+/// Lends `change` to friendly units within `radius` for as long as they stay
+/// there.
+#[derive(Component, Debug, Clone)]
+pub struct Aura {
+    pub name: String,
+    pub radius: f32,
+    pub stat: Stat,
+    pub change: StatChange,
+}
+
+/// `base` with whatever effects a unit has on `stat`.
+pub fn effective(effects: Option<&StatEffects>, stat: Stat, base: f32) -> f32 {
+    effects.map_or(base, |effects| effects.apply(stat, base))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ModifierStack, Stat, StatChange, StatEffect, StatEffects, StatModifier};
+
+    #[test]
+    fn additions_apply_before_percentages() {
+        let stack = ModifierStack {
+            modifiers: vec![
+                StatModifier {
+                    stat: Stat::Health,
+                    change: StatChange::Percent(20.),
+                    unit_types: vec![],
+                },
+                StatModifier {
+                    stat: Stat::Health,
+                    change: StatChange::Add(50.),
+                    unit_types: vec!["ship".to_string()],
+                },
+                StatModifier {
+                    stat: Stat::Speed,
+                    change: StatChange::Add(1.),
+                    unit_types: vec![],
+                },
+            ],
+        };
+
+        assert_eq!(stack.apply(Stat::Health, "ship", 100.), 180.);
+        assert_eq!(stack.apply(Stat::Health, "skelly", 100.), 120.);
+    }
+
+    #[test]
+    fn effects_refresh_by_source_and_expire() {
+        let mut effects = StatEffects::default();
+        effects.add(StatEffect::new(
+            "Slow",
+            Stat::Speed,
+            StatChange::Multiply(0.5),
+            1.,
+        ));
+        effects.add(StatEffect::new(
+            "Haste",
+            Stat::Speed,
+            StatChange::Percent(50.),
+            3.,
+        ));
+        effects.tick(Duration::from_secs_f32(0.5));
+        effects.add(StatEffect::new(
+            "Slow",
+            Stat::Speed,
+            StatChange::Multiply(0.5),
+            1.,
+        ));
+
+        assert_eq!(effects.apply(Stat::Speed, 2.), 1.5);
+        assert_eq!(effects.apply(Stat::Damage, 8.), 8.);
+
+        effects.tick(Duration::from_secs_f32(1.));
+        assert_eq!(effects.apply(Stat::Speed, 2.), 3.);
+        effects.tick(Duration::from_secs_f32(2.));
+        assert!(effects.effects.is_empty());
+    }
+
+    #[test]
+    fn health_cannot_be_timed() {
+        let mut effects = StatEffects::default();
+        effects.add(StatEffect::new(
+            "Shield",
+            Stat::Health,
+            StatChange::Add(50.),
+            5.,
+        ));
+
+        assert!(effects.effects.is_empty());
+    }
+}
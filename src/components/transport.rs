@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+/// Something units can ride in or garrison: a transport ship, or a building
+/// once it is finished. `cargo` holds the units inside, in the order they got
+/// in.
+#[derive(Component, Debug, Default, Clone, PartialEq)]
+pub struct Transport {
+    pub capacity: usize,
+    pub cargo: Vec<Entity>,
+}
+
+impl Transport {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            cargo: Vec::new(),
+        }
+    }
+
+    /// How many more units fit.
+    pub fn room(&self) -> usize {
+        self.capacity.saturating_sub(self.cargo.len())
+    }
+}
+
+/// A unit inside `carrier`. Loaded units lose their `Unit` marker and their
+/// order while inside, so they are neither seen, targeted, selected nor
+/// simulated until they are unloaded.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Loaded {
+    pub carrier: Entity,
+}
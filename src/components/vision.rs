@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    components::mechanics::Team,
+    constants::{plane::*, vision::FOG_CELL_SIZE},
+};
+
+/// How far a unit sees. Units without one see `DEFAULT_SIGHT_RANGE`.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct SightRange {
+    pub value: f32,
+}
+
+/// Something that blocks sight lines, standing `height` tall over a disc of
+/// `radius` around its position.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct Obstacle {
+    pub radius: f32,
+    pub height: f32,
+}
+
+/// Set on enemy units the player cannot currently see.
+#[derive(Component, Debug)]
+pub struct Fogged;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FogState {
+    #[default]
+    Unexplored,
+    /// Seen before but not right now.
+    Explored,
+    Visible,
+}
+
+/// What one team has seen of the map, on a grid of `FOG_CELL_SIZE` squares.
+#[derive(Debug, Clone)]
+pub struct TeamVision {
+    pub width: usize,
+    pub depth: usize,
+    cells: Vec<FogState>,
+}
+
+impl Default for TeamVision {
+    fn default() -> Self {
+        Self::new(
+            ((GAME_X_MAX - GAME_X_MIN) / FOG_CELL_SIZE).ceil() as usize,
+            ((GAME_Z_MAX - GAME_Z_MIN) / FOG_CELL_SIZE).ceil() as usize,
+        )
+    }
+}
+
+impl TeamVision {
+    pub fn new(width: usize, depth: usize) -> Self {
+        Self {
+            width,
+            depth,
+            cells: vec![FogState::Unexplored; width * depth],
+        }
+    }
+
+    /// The `(x, z)` fog cell under `position`, if it is on the map.
+    pub fn cell_index(&self, position: Vec3) -> Option<(usize, usize)> {
+        let x = (position.x - GAME_X_MIN) / FOG_CELL_SIZE;
+        let z = (position.z - GAME_Z_MIN) / FOG_CELL_SIZE;
+        if x < 0. || z < 0. || x >= self.width as f32 || z >= self.depth as f32 {
+            return None;
+        }
+        Some((x as usize, z as usize))
+    }
+
+    /// The middle of a fog cell, at `y`.
+    pub fn cell_center(&self, (x, z): (usize, usize), y: f32) -> Vec3 {
+        Vec3::new(
+            GAME_X_MIN + (x as f32 + 0.5) * FOG_CELL_SIZE,
+            y,
+            GAME_Z_MIN + (z as f32 + 0.5) * FOG_CELL_SIZE,
+        )
+    }
+
+    pub fn get(&self, (x, z): (usize, usize)) -> FogState {
+        self.cells[z * self.width + x]
+    }
+
+    pub fn state_at(&self, position: Vec3) -> FogState {
+        self.cell_index(position)
+            .map_or(FogState::Unexplored, |index| self.get(index))
+    }
+
+    pub fn is_visible(&self, position: Vec3) -> bool {
+        self.state_at(position) == FogState::Visible
+    }
+
+    /// Turns everything visible into explored, ready for this frame's sight.
+    pub fn fade(&mut self) {
+        for cell in &mut self.cells {
+            if *cell == FogState::Visible {
+                *cell = FogState::Explored;
+            }
+        }
+    }
+
+    /// Marks every cell whose centre is within `radius` of `center` and
+    /// passes `can_see` as visible.
+    pub fn reveal(&mut self, center: Vec3, radius: f32, can_see: impl FnMut(Vec3) -> bool) {
+        let cells = self.visible_cells(center, radius, can_see);
+        self.show(&cells);
+    }
+
+    /// The cells `reveal` would mark, to be shown again with `show`.
+    pub fn visible_cells(
+        &self,
+        center: Vec3,
+        radius: f32,
+        mut can_see: impl FnMut(Vec3) -> bool,
+    ) -> Vec<(usize, usize)> {
+        let mut cells = Vec::new();
+        let reach = (radius / FOG_CELL_SIZE).ceil() as isize;
+        let Some((cx, cz)) = self.cell_index(center) else {
+            return cells;
+        };
+        for dz in -reach..=reach {
+            for dx in -reach..=reach {
+                let (x, z) = (cx as isize + dx, cz as isize + dz);
+                if x < 0 || z < 0 || x >= self.width as isize || z >= self.depth as isize {
+                    continue;
+                }
+                let index = (x as usize, z as usize);
+                let cell_center = self.cell_center(index, center.y);
+                if (cell_center - center).length_squared() > radius * radius
+                    || !can_see(cell_center)
+                {
+                    continue;
+                }
+                cells.push(index);
+            }
+        }
+        cells
+    }
+
+    pub fn show(&mut self, cells: &[(usize, usize)]) {
+        for (x, z) in cells {
+            self.cells[z * self.width + x] = FogState::Visible;
+        }
+    }
+}
+
+/// Every team's vision of the map.
+#[derive(Resource, Debug, Default)]
+pub struct FogOfWar {
+    pub teams: HashMap<Team, TeamVision>,
+}
+
+impl FogOfWar {
+    pub fn vision(&self, team: Team) -> Option<&TeamVision> {
+        self.teams.get(&team)
+    }
+
+    /// Whether `team` sees `position` right now.
+    pub fn is_visible(&self, team: Team, position: Vec3) -> bool {
+        self.vision(team)
+            .map_or(false, |vision| vision.is_visible(position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{FogState, TeamVision};
+
+    #[test]
+    fn revealed_cells_turn_explored_when_sight_moves_on() {
+        let mut vision = TeamVision::new(10, 10);
+        let here = Vec3::new(2.5, 0., 2.5);
+
+        vision.reveal(here, 1.5, |_| true);
+        assert_eq!(vision.state_at(here), FogState::Visible);
+        assert_eq!(
+            vision.state_at(Vec3::new(8.5, 0., 8.5)),
+            FogState::Unexplored
+        );
+
+        vision.fade();
+        assert_eq!(vision.state_at(here), FogState::Explored);
+    }
+
+    #[test]
+    fn reveal_stays_inside_its_radius_and_the_map() {
+        let mut vision = TeamVision::new(10, 10);
+
+        vision.reveal(Vec3::new(0.5, 0., 0.5), 2., |_| true);
+
+        assert!(vision.is_visible(Vec3::new(2.5, 0., 0.5)));
+        assert!(!vision.is_visible(Vec3::new(2.5, 0., 2.5)));
+        assert!(!vision.is_visible(Vec3::new(-0.5, 0., 0.5)));
+    }
+}
@@ -1,2 +1,15 @@
 pub const CAMERA_ROTATION_SPEED: f32 = 2.5;
 pub const CAMERA_MOVEMENT_SPEED: f32 = 25.0;
+
+pub const CAMERA_START_HEIGHT: f32 = 20.0;
+pub const CAMERA_START_YAW_DEGREES: f32 = 45.0;
+pub const CAMERA_MIN_HEIGHT: f32 = 6.0;
+pub const CAMERA_MAX_HEIGHT: f32 = 45.0;
+// Pitch follows zoom: shallow when close to the ground, steep when far away.
+pub const CAMERA_MIN_PITCH_DEGREES: f32 = 35.0;
+pub const CAMERA_MAX_PITCH_DEGREES: f32 = 70.0;
+pub const CAMERA_ZOOM_SPEED: f32 = 2.0;
+pub const CAMERA_EDGE_SCROLL_MARGIN: f32 = 12.0;
+pub const CAMERA_DRAG_SPEED: f32 = 0.0025;
+// Higher is snappier; the fraction of the remaining distance covered per second.
+pub const CAMERA_SMOOTHING: f32 = 10.0;
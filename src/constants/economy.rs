@@ -0,0 +1,18 @@
+use bevy::prelude::Color;
+
+pub const ECONOMY_FILE: &str = "assets/data/economy.ron";
+// How far from the cursor a resource node can be and still count as clicked.
+pub const RESOURCE_NODE_CLICK_RADIUS: f32 = 1.5;
+pub const RESOURCE_NODE_SIZE: f32 = 1.2;
+pub const RESOURCE_NODE_COLOR: Color = Color::rgb(0.2, 0.75, 0.9);
+pub const DEPOT_SIZE: f32 = 2.0;
+
+pub const BUILDINGS_FILE: &str = "assets/data/buildings.ron";
+pub const RESEARCH_FILE: &str = "assets/data/research.ron";
+pub const BUILDING_HEIGHT: f32 = 2.5;
+// Buildings are drawn slightly smaller than their footprint so neighbours read apart.
+pub const BUILDING_INSET: f32 = 0.9;
+pub const PLACEMENT_VALID_COLOR: Color = Color::rgba(0.2, 0.9, 0.3, 0.4);
+pub const PLACEMENT_INVALID_COLOR: Color = Color::rgba(0.9, 0.2, 0.2, 0.4);
+// Units a building can have queued at once, including the one in progress.
+pub const PRODUCTION_QUEUE_LIMIT: usize = 5;
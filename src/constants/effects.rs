@@ -0,0 +1,10 @@
+use bevy::prelude::Color;
+
+// Flashes go to their colour and back, in this many seconds all told.
+pub const HIT_FLASH_COLOR: Color = Color::rgb(1.0, 0.35, 0.25);
+pub const HIT_FLASH_DURATION: f32 = 0.2;
+pub const SELECTION_FLASH_COLOR: Color = Color::rgb(0.2, 0.8, 0.35);
+pub const SELECTION_FLASH_DURATION: f32 = 0.4;
+// New units grow from this share of their size.
+pub const SPAWN_START_SCALE: f32 = 0.2;
+pub const SPAWN_GROW_DURATION: f32 = 0.35;
@@ -0,0 +1,30 @@
+use bevy::prelude::Color;
+
+pub const HUD_FONT: &str = "fonts/FiraSans-Bold.ttf";
+pub const HUD_FONT_SIZE: f32 = 14.0;
+pub const HUD_TEXT_COLOR: Color = Color::WHITE;
+pub const HUD_BACKGROUND_COLOR: Color = Color::rgba(0.05, 0.05, 0.08, 0.8);
+pub const HUD_MARGIN: f32 = 10.0;
+
+pub const SELECTION_PANEL_HEIGHT: f32 = 110.0;
+pub const SELECTION_ICON_WIDTH: f32 = 20.0;
+pub const SELECTION_ICON_HEIGHT: f32 = 24.0;
+// More icons than this are summarised by the per-type counts.
+pub const SELECTION_MAX_ICONS: usize = 48;
+
+pub const HEALTH_BAR_HEIGHT: f32 = 4.0;
+pub const HEALTH_BAR_BACKGROUND_COLOR: Color = Color::rgb(0.25, 0.0, 0.0);
+pub const HEALTH_BAR_HEALTHY_COLOR: Color = Color::rgb(0.1, 0.9, 0.2);
+pub const HEALTH_BAR_DAMAGED_COLOR: Color = Color::rgb(0.95, 0.2, 0.1);
+
+pub const COMMAND_CARD_COLUMNS: usize = 3;
+pub const COMMAND_BUTTON_WIDTH: f32 = 64.0;
+pub const COMMAND_BUTTON_HEIGHT: f32 = 32.0;
+pub const COMMAND_BUTTON_MARGIN: f32 = 2.0;
+pub const COMMAND_CARD_WIDTH: f32 =
+    (COMMAND_BUTTON_WIDTH + COMMAND_BUTTON_MARGIN * 2.) * COMMAND_CARD_COLUMNS as f32 + 8.0;
+pub const COMMAND_BUTTON_COLOR: Color = Color::rgb(0.15, 0.17, 0.22);
+pub const COMMAND_BUTTON_HOVERED_COLOR: Color = Color::rgb(0.25, 0.28, 0.36);
+// The order waiting for a target click.
+pub const COMMAND_BUTTON_ACTIVE_COLOR: Color = Color::rgb(0.2, 0.45, 0.3);
+pub const COMMAND_COOLDOWN_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.65);
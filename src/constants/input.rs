@@ -0,0 +1,3 @@
+pub const BINDINGS_FILE: &str = "assets/config/bindings.ron";
+/// Rebinds made in game, on top of the shipped bindings file.
+pub const USER_BINDINGS_FILE: &str = "bindings.user.ron";
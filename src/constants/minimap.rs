@@ -0,0 +1,16 @@
+use bevy::prelude::Color;
+
+pub const MINIMAP_SIZE: f32 = 150.0;
+pub const MINIMAP_MARGIN: f32 = 10.0;
+pub const MINIMAP_TEXTURE_SIZE: u32 = 64;
+pub const MINIMAP_BLIP_SIZE: f32 = 4.0;
+pub const MINIMAP_FRUSTUM_THICKNESS: f32 = 1.0;
+pub const MINIMAP_FRUSTUM_COLOR: Color = Color::WHITE;
+
+// Indexed by `Team`; teams past the end reuse the last colour.
+pub const TEAM_COLORS: [Color; 4] = [
+    Color::rgb(0.2, 0.6, 1.0),
+    Color::rgb(1.0, 0.25, 0.2),
+    Color::rgb(1.0, 0.85, 0.2),
+    Color::rgb(0.7, 0.3, 1.0),
+];
@@ -1,5 +1,13 @@
 pub mod camera;
 pub mod constants;
+pub mod economy;
+pub mod effects;
+pub mod hud;
+pub mod input;
 pub mod mechanics;
+pub mod minimap;
+pub mod overlays;
 pub mod plane;
+pub mod selection;
 pub mod units;
+pub mod vision;
@@ -0,0 +1,19 @@
+use bevy::prelude::Color;
+
+pub const SELECTION_RING_INNER_RADIUS: f32 = 0.85;
+pub const SELECTION_RING_OUTER_RADIUS: f32 = 1.0;
+pub const SELECTION_RING_SEGMENTS: usize = 24;
+// Lift rings off the ground so they do not z-fight with it.
+pub const SELECTION_RING_HEIGHT: f32 = 0.05;
+pub const SELECTION_RING_COLOR: Color = Color::rgb(0.2, 1.0, 0.3);
+
+pub const WORLD_HEALTH_BAR_WIDTH: f32 = 1.2;
+pub const WORLD_HEALTH_BAR_HEIGHT: f32 = 0.15;
+pub const WORLD_HEALTH_BAR_OFFSET: f32 = 1.8;
+
+pub const EXPLOSION_COLOR: Color = Color::rgba(1.0, 0.55, 0.1, 0.8);
+// Explosions grow from this share of their radius, and fade out over their
+// lifetime.
+pub const EXPLOSION_START_SCALE: f32 = 0.4;
+pub const EXPLOSION_GROW_DURATION: f32 = 0.25;
+pub const EXPLOSION_LIFETIME: f32 = 0.6;
@@ -4,6 +4,10 @@ pub const BOARD_SIZE_I: f32 = 3.;
 pub const BOARD_SIZE_J: f32 = 3.;
 // pub const BLOCK_SIZE_PIXELS: usize = 126;
 pub const BLOCK_SIZE: f32 = 15.75;
+// Each block is split into a 2x2 of board cells.
+pub const CELL_SIZE: f32 = BLOCK_SIZE / 2.;
+// Units steer around the cells of finished buildings as circles through their corners.
+pub const BLOCKED_CELL_RADIUS: f32 = CELL_SIZE * std::f32::consts::FRAC_1_SQRT_2;
 pub const GAME_X_MIN: f32 = 0.0;
 pub const GAME_Z_MIN: f32 = 0.0;
 pub const GAME_X_MAX: f32 = 24.0;
@@ -0,0 +1,6 @@
+pub const CONTROL_GROUPS: usize = 10;
+// Seconds between two presses of the same control group to focus the camera on it.
+pub const CONTROL_GROUP_DOUBLE_TAP: f64 = 0.35;
+pub const DOUBLE_CLICK_SECONDS: f64 = 0.3;
+// How far from the cursor a unit can be and still count as clicked.
+pub const CLICK_SELECT_RADIUS: f32 = 1.5;
@@ -2,3 +2,36 @@
 // pub const SHIP_STEPS
 pub const ARRIVAL_TOLERANCE: f32 = 0.75;
 pub const SOCIAL_DISTANCE: f32 = 1.7;
+// Physics units are balls this big, whatever their model's scale.
+pub const UNIT_COLLIDER_RADIUS: f32 = 0.85;
+pub const PHYSICS_LINEAR_DAMPING: f32 = 2.;
+// Local avoidance: how big units are to each other, how far ahead (seconds)
+// and how far around they look.
+pub const AVOIDANCE_RADIUS: f32 = SOCIAL_DISTANCE / 2.;
+pub const AVOIDANCE_TIME_HORIZON: f32 = 1.;
+pub const AVOIDANCE_NEIGHBOUR_DISTANCE: f32 = 8.;
+// Stuck detection defaults for unit types that do not set their own: seconds
+// without getting this much closer, then how many detours and nudges to try.
+pub const STUCK_TIMEOUT: f32 = 2.;
+pub const STUCK_MIN_PROGRESS: f32 = 0.5;
+pub const STUCK_REPATHS: u8 = 1;
+pub const STUCK_NUDGES: u8 = 1;
+pub const DETOUR_DISTANCE: f32 = 3.;
+pub const NUDGE_DISTANCE: f32 = 1.;
+// Guards keep this far from their ward, catch up once they trail it by more
+// than the slack and stop chasing attackers beyond the leash.
+pub const GUARD_DISTANCE: f32 = 2.5;
+pub const GUARD_FOLLOW_SLACK: f32 = 1.;
+pub const GUARD_LEASH: f32 = 10.;
+// Units attack-moving take on enemies this close, or within weapon range if
+// that is further, and let them go once they get further away than that.
+pub const ACQUISITION_RADIUS: f32 = 8.;
+// Transports take in units this close and set them down this far apart.
+pub const LOAD_RADIUS: f32 = 4.;
+pub const UNLOAD_SPACING: f32 = 2.;
+// Seconds an aura's effect outlasts leaving its radius.
+pub const AURA_LINGER: f32 = 0.5;
+pub const UNITS_FILE: &str = "assets/data/units.ron";
+pub const SHIP_SCALE: f32 = 2.;
+// Distance between neighbours when spawning a batch of units.
+pub const SPAWN_SPACING: f32 = 3.;
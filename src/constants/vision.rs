@@ -0,0 +1,14 @@
+pub const FOG_CELL_SIZE: f32 = 1.0;
+pub const DEFAULT_SIGHT_RANGE: f32 = 8.0;
+// Just above the selection rings so it darkens them too.
+pub const FOG_OVERLAY_HEIGHT: f32 = 0.08;
+// How much of the board is blacked out in each fog state, from 0 to 255.
+pub const FOG_UNEXPLORED_ALPHA: u8 = 235;
+pub const FOG_EXPLORED_ALPHA: u8 = 140;
+
+// Sight lines run between points this far above the ground.
+pub const EYE_HEIGHT: f32 = 1.0;
+// Distance between the points checked along a sight line.
+pub const LINE_OF_SIGHT_STEP: f32 = 0.5;
+// Extra sight range for each unit of height a unit stands above the ground.
+pub const HIGH_GROUND_SIGHT_BONUS: f32 = 2.0;
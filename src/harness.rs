@@ -0,0 +1,982 @@
+use std::time::{Duration, Instant};
+
+use bevy::{core::CorePlugin, prelude::*, scene::ScenePlugin};
+use bevy_rapier3d::prelude::{NoUserData, RapierPhysicsPlugin};
+
+use crate::{
+    components::{
+        mechanics::{Health, MovementSpeed, RotationSpeed, Unit},
+        orders::Order,
+        stats::StatEffects,
+    },
+    constants::constants::GROUND_LEVEL,
+    systems::{
+        commands::{CommandEvent, UnitCommand},
+        movement::physics_body,
+    },
+    HeadlessPlugins,
+};
+
+pub const SIMULATION_TICK: f32 = 1. / 60.;
+
+/// Drives a headless `App` one fixed tick at a time.
+///
+/// Time is advanced by hand instead of by `TimePlugin`, so a run of N ticks
+/// produces the same positions on every machine.
+/// https://github.com/bevyengine/bevy/blob/main/tests/how_to_test_systems.rs
+pub struct SimulationHarness {
+    pub app: App,
+    now: Instant,
+    tick: Duration,
+}
+
+impl Default for SimulationHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimulationHarness {
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin::default())
+            .init_resource::<Time>()
+            .add_plugins(HeadlessPlugins);
+
+        let now = Instant::now();
+        app.world.resource_mut::<Time>().update_with_instant(now);
+
+        Self {
+            app,
+            now,
+            tick: Duration::from_secs_f32(SIMULATION_TICK),
+        }
+    }
+
+    /// Like `new`, with Rapier stepping units under physics movement. Rapier
+    /// wants the mesh and scene assets around even though nothing loads any.
+    pub fn with_physics() -> Self {
+        let mut sim = Self::new();
+        sim.app
+            .add_plugin(AssetPlugin::default())
+            .add_asset::<Mesh>()
+            .add_plugin(ScenePlugin)
+            .add_plugin(RapierPhysicsPlugin::<NoUserData>::default());
+        sim
+    }
+
+    /// An idle unit without a type, so no definition overrides its stats.
+    pub fn spawn_unit(&mut self, x: f32, z: f32) -> Entity {
+        self.app
+            .world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_xyz(x, GROUND_LEVEL, z)),
+                Unit,
+                Health::new(100.),
+                MovementSpeed { value: 2. },
+                RotationSpeed { value: 150. },
+                StatEffects::default(),
+                Order::Idle,
+            ))
+            .id()
+    }
+
+    pub fn spawn_physics_unit(&mut self, x: f32, z: f32) -> Entity {
+        let unit = self.spawn_unit(x, z);
+        self.app.world.entity_mut(unit).insert(physics_body());
+        unit
+    }
+
+    pub fn command(&mut self, units: Vec<Entity>, command: UnitCommand) {
+        self.app
+            .world
+            .resource_mut::<Events<CommandEvent>>()
+            .send(CommandEvent { units, command });
+    }
+
+    pub fn step(&mut self, ticks: usize) {
+        for _ in 0..ticks {
+            self.now += self.tick;
+            self.app
+                .world
+                .resource_mut::<Time>()
+                .update_with_instant(self.now);
+            self.app.update();
+        }
+    }
+
+    pub fn position(&self, entity: Entity) -> Vec3 {
+        self.app
+            .world
+            .get::<Transform>(entity)
+            .expect("entity has no Transform")
+            .translation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Events, Transform, TransformBundle, Vec3};
+
+    use super::{SimulationHarness, SIMULATION_TICK};
+    use crate::{
+        components::{
+            buildings::{Building, ProductionQueue, RallyPoint, UnderConstruction},
+            economy::{Depot, EconomyConfig, Harvester, ResourceNode, Stockpiles},
+            mechanics::{
+                Destination, Detour, Health, MovementSpeed, Splash, Team, Unit, UnitType, Weapon,
+            },
+            orders::Order,
+            research::{ResearchDefinitions, Researching, TeamResearch},
+            stats::{Aura, Stat, StatChange, StatEffects},
+            transport::{Loaded, Transport},
+            vision::{FogOfWar, FogState, Fogged},
+        },
+        constants::{
+            constants::GROUND_LEVEL,
+            plane::CELL_SIZE,
+            units::{
+                ARRIVAL_TOLERANCE, AURA_LINGER, AVOIDANCE_RADIUS, GUARD_DISTANCE,
+                GUARD_FOLLOW_SLACK, GUARD_LEASH, UNIT_COLLIDER_RADIUS, UNLOAD_SPACING,
+            },
+        },
+        systems::{
+            buildings::PlaceBuildingEvent,
+            combat::{AreaDamageEvent, DamageEvent},
+            commands::UnitCommand,
+            stuck::OrderFailed,
+        },
+        units::definitions::UnitDefinitions,
+        util::are_positions_near,
+        Game,
+    };
+
+    #[test]
+    fn unit_reaches_destination_after_move_command() {
+        let mut sim = SimulationHarness::new();
+        let unit = sim.spawn_unit(5., 5.);
+        let destination = Vec3::new(15., GROUND_LEVEL, 15.);
+
+        sim.command(vec![unit], UnitCommand::Move(destination));
+        sim.step(200);
+
+        assert!(are_positions_near(
+            &destination,
+            &sim.position(unit),
+            ARRIVAL_TOLERANCE
+        ));
+        assert!(sim.app.world.get::<Destination>(unit).is_none());
+    }
+
+    #[test]
+    fn stop_command_halts_unit_before_destination() {
+        let mut sim = SimulationHarness::new();
+        let unit = sim.spawn_unit(5., 5.);
+        let destination = Vec3::new(15., GROUND_LEVEL, 15.);
+
+        sim.command(vec![unit], UnitCommand::Move(destination));
+        sim.step(10);
+        sim.command(vec![unit], UnitCommand::Stop);
+        sim.step(1);
+        let stopped_at = sim.position(unit);
+        sim.step(100);
+
+        assert!(are_positions_near(&stopped_at, &sim.position(unit), 0.01));
+        assert!(!are_positions_near(
+            &destination,
+            &sim.position(unit),
+            ARRIVAL_TOLERANCE
+        ));
+    }
+
+    #[test]
+    fn idle_unit_stays_put() {
+        let mut sim = SimulationHarness::new();
+        let unit = sim.spawn_unit(10., 10.);
+
+        sim.step(60);
+
+        assert!(are_positions_near(
+            &Vec3::new(10., GROUND_LEVEL, 10.),
+            &sim.position(unit),
+            0.01
+        ));
+    }
+
+    #[test]
+    fn unit_is_despawned_when_health_runs_out() {
+        let mut sim = SimulationHarness::new();
+        let unit = sim.spawn_unit(10., 10.);
+
+        sim.app
+            .world
+            .resource_mut::<Events<DamageEvent>>()
+            .send(DamageEvent {
+                target: unit,
+                amount: 150.,
+                source: None,
+            });
+        sim.step(2);
+
+        assert!(sim.app.world.get_entity(unit).is_none());
+    }
+
+    #[test]
+    fn attacking_unit_chases_and_kills_its_target() {
+        let mut sim = SimulationHarness::new();
+        let attacker = sim.spawn_unit(5., 5.);
+        let target = sim.spawn_unit(20., 5.);
+        sim.app
+            .world
+            .entity_mut(attacker)
+            .insert(Weapon::new(50., 3., 0.5));
+        sim.app.world.entity_mut(target).insert(Team(1));
+
+        sim.command(vec![attacker], UnitCommand::Attack(target));
+        sim.step(600);
+
+        assert!(sim.app.world.get_entity(target).is_none());
+        assert_eq!(sim.app.world.get::<Order>(attacker), Some(&Order::Idle));
+    }
+
+    #[test]
+    fn attack_moving_unit_fights_what_it_meets_then_carries_on() {
+        let mut sim = SimulationHarness::new();
+        let attacker = sim.spawn_unit(5., 10.);
+        let enemy = sim.spawn_unit(15., 13.);
+        let destination = Vec3::new(25., GROUND_LEVEL, 10.);
+        sim.app
+            .world
+            .entity_mut(attacker)
+            .insert(Weapon::new(50., 3., 0.5));
+        sim.app.world.entity_mut(enemy).insert(Team(1));
+
+        sim.command(vec![attacker], UnitCommand::AttackMove(destination));
+        let mut engaged = false;
+        for _ in 0..900 {
+            sim.step(1);
+            engaged |= matches!(
+                sim.app.world.get::<Order>(attacker),
+                Some(Order::AttackMove {
+                    engaging: Some(_),
+                    ..
+                })
+            );
+        }
+
+        assert!(engaged);
+        assert!(sim.app.world.get_entity(enemy).is_none());
+        assert!(are_positions_near(
+            &destination,
+            &sim.position(attacker),
+            ARRIVAL_TOLERANCE
+        ));
+        assert_eq!(sim.app.world.get::<Order>(attacker), Some(&Order::Idle));
+    }
+
+    #[test]
+    fn holding_unit_fires_without_moving() {
+        let mut sim = SimulationHarness::new();
+        let holder = sim.spawn_unit(10., 10.);
+        let enemy = sim.spawn_unit(12., 10.);
+        sim.app
+            .world
+            .entity_mut(holder)
+            .insert(Weapon::new(10., 3., 0.5));
+        sim.app.world.entity_mut(enemy).insert(Team(1));
+
+        sim.command(vec![holder], UnitCommand::Hold);
+        sim.step(60);
+
+        assert!(are_positions_near(
+            &Vec3::new(10., GROUND_LEVEL, 10.),
+            &sim.position(holder),
+            0.01
+        ));
+        assert!(sim.app.world.get::<Health>(enemy).unwrap().value < 100.);
+    }
+
+    #[test]
+    fn patrol_turns_around_at_its_end() {
+        let mut sim = SimulationHarness::new();
+        let unit = sim.spawn_unit(5., 5.);
+        let start = sim.position(unit);
+        let end = Vec3::new(15., GROUND_LEVEL, 5.);
+
+        sim.command(vec![unit], UnitCommand::Patrol(end));
+        sim.step(100);
+
+        assert_eq!(
+            sim.app.world.get::<Order>(unit),
+            Some(&Order::Patrol {
+                points: vec![start, end],
+                next: 0
+            })
+        );
+        assert!(sim.app.world.get::<Destination>(unit).is_some());
+    }
+
+    #[test]
+    fn patrol_with_added_points_loops_through_all_of_them() {
+        let mut sim = SimulationHarness::new();
+        let unit = sim.spawn_unit(5., 5.);
+        let start = sim.position(unit);
+        let third = Vec3::new(15., GROUND_LEVEL, 15.);
+
+        sim.command(
+            vec![unit],
+            UnitCommand::Patrol(Vec3::new(15., GROUND_LEVEL, 5.)),
+        );
+        sim.step(1);
+        sim.command(vec![unit], UnitCommand::AddPatrolPoint(third));
+        let mut reached_third = false;
+        let mut back_at_start = false;
+        for _ in 0..1200 {
+            sim.step(1);
+            let position = sim.position(unit);
+            reached_third |= are_positions_near(&third, &position, ARRIVAL_TOLERANCE);
+            back_at_start |=
+                reached_third && are_positions_near(&start, &position, ARRIVAL_TOLERANCE);
+        }
+
+        assert!(reached_third);
+        assert!(back_at_start);
+        assert!(matches!(
+            sim.app.world.get::<Order>(unit),
+            Some(Order::Patrol { points, .. }) if points.len() == 3
+        ));
+    }
+
+    #[test]
+    fn guard_follows_its_ward_and_goes_idle_when_it_is_gone() {
+        let mut sim = SimulationHarness::new();
+        let ward = sim.spawn_unit(10., 10.);
+        let guard = sim.spawn_unit(8., 10.);
+
+        sim.command(vec![guard], UnitCommand::Guard(ward));
+        sim.command(
+            vec![ward],
+            UnitCommand::Move(Vec3::new(20., GROUND_LEVEL, 14.)),
+        );
+        sim.step(400);
+
+        let post = sim.position(ward) + Vec3::new(-GUARD_DISTANCE, 0., 0.);
+        assert!(
+            sim.position(guard).distance(post) <= GUARD_FOLLOW_SLACK + ARRIVAL_TOLERANCE,
+            "guard at {:?}, post at {:?}",
+            sim.position(guard),
+            post
+        );
+
+        sim.app.world.despawn(ward);
+        sim.step(2);
+
+        assert_eq!(sim.app.world.get::<Order>(guard), Some(&Order::Idle));
+    }
+
+    #[test]
+    fn guard_engages_whoever_attacks_its_ward() {
+        let mut sim = SimulationHarness::new();
+        let ward = sim.spawn_unit(10., 10.);
+        let guard = sim.spawn_unit(8., 10.);
+        let enemy = sim.spawn_unit(16., 10.);
+        sim.app
+            .world
+            .entity_mut(guard)
+            .insert(Weapon::new(50., 3., 0.5));
+        // Reaches the ward but not the guard.
+        sim.app
+            .world
+            .entity_mut(enemy)
+            .insert((Team(1), Weapon::new(5., 7., 0.5)));
+
+        sim.command(vec![guard], UnitCommand::Guard(ward));
+        sim.step(600);
+
+        assert!(sim.app.world.get_entity(enemy).is_none());
+        assert!(sim.app.world.get::<Health>(ward).unwrap().value < 100.);
+        assert!(matches!(
+            sim.app.world.get::<Order>(guard),
+            Some(Order::Guard { engaging: None, .. })
+        ));
+        assert!(sim.position(guard).distance(sim.position(ward)) <= GUARD_LEASH);
+    }
+
+    #[test]
+    fn harvester_brings_cargo_back_to_the_depot() {
+        // The economy file puts a player depot at (4, 4).
+        let mut sim = SimulationHarness::new();
+        let harvester = sim.spawn_unit(6., 6.);
+        sim.app.world.entity_mut(harvester).insert(Harvester {
+            gather_rate: 20.,
+            capacity: 10.,
+            ..Default::default()
+        });
+        let node = sim
+            .app
+            .world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_xyz(10., GROUND_LEVEL, 6.)),
+                ResourceNode { remaining: 100. },
+            ))
+            .id();
+        let starting = sim.app.world.resource::<EconomyConfig>().starting_resources;
+
+        sim.command(vec![harvester], UnitCommand::Gather(node));
+        sim.step(300);
+
+        let stockpile = sim.app.world.resource::<Stockpiles>().get(Team::PLAYER);
+        assert!(stockpile >= starting + 10.);
+        assert!(sim.app.world.get::<ResourceNode>(node).unwrap().remaining <= 90.);
+    }
+
+    #[test]
+    fn placed_building_is_paid_for_and_blocks_its_cell_once_built() {
+        let mut sim = SimulationHarness::new();
+        sim.step(1);
+        let starting = sim.app.world.resource::<Stockpiles>().get(Team::PLAYER);
+        let position = Vec3::new(CELL_SIZE * 1.5, GROUND_LEVEL, CELL_SIZE * 1.5);
+        let place = || PlaceBuildingEvent {
+            kind: "depot".to_string(),
+            team: Team::PLAYER,
+            position,
+        };
+
+        sim.app
+            .world
+            .resource_mut::<Events<PlaceBuildingEvent>>()
+            .send(place());
+        sim.step(2);
+        // The cell is taken now, so a second one is turned down without charge.
+        sim.app
+            .world
+            .resource_mut::<Events<PlaceBuildingEvent>>()
+            .send(place());
+        sim.step(2);
+
+        let occupant = sim.app.world.resource::<Game>().board[1][1].occupant;
+        let building = occupant.expect("cell was not reserved");
+        assert!(sim.app.world.get::<UnderConstruction>(building).is_some());
+        assert_eq!(
+            sim.app.world.resource::<Stockpiles>().get(Team::PLAYER),
+            starting - 150.
+        );
+
+        // The depot takes ten seconds to build.
+        sim.step(600);
+
+        assert!(sim.app.world.resource::<Game>().board[1][1].blocked);
+        assert!(sim.app.world.get::<UnderConstruction>(building).is_none());
+        assert!(sim.app.world.get::<Depot>(building).is_some());
+        assert_eq!(
+            sim.app
+                .world
+                .query::<&Building>()
+                .iter(&sim.app.world)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn units_walk_around_finished_buildings() {
+        let mut sim = SimulationHarness::new();
+        sim.step(1);
+        sim.app
+            .world
+            .resource_mut::<Events<PlaceBuildingEvent>>()
+            .send(PlaceBuildingEvent {
+                kind: "depot".to_string(),
+                team: Team::PLAYER,
+                position: Vec3::new(CELL_SIZE * 1.5, GROUND_LEVEL, CELL_SIZE * 1.5),
+            });
+        sim.step(610);
+        assert!(sim.app.world.resource::<Game>().board[1][1].blocked);
+        let unit = sim.spawn_unit(3., 11.);
+        let destination = Vec3::new(21., GROUND_LEVEL, 11.);
+
+        sim.command(vec![unit], UnitCommand::Move(destination));
+        for _ in 0..600 {
+            sim.step(1);
+            let position = sim.position(unit);
+            let inside = |v: f32| v > CELL_SIZE && v < CELL_SIZE * 2.;
+            assert!(!(inside(position.x) && inside(position.z)));
+        }
+
+        assert!(are_positions_near(
+            &destination,
+            &sim.position(unit),
+            ARRIVAL_TOLERANCE
+        ));
+    }
+
+    #[test]
+    fn shipyard_produces_ships_that_head_for_its_rally_point() {
+        let mut sim = SimulationHarness::new();
+        sim.step(1);
+        sim.app
+            .world
+            .resource_mut::<Events<PlaceBuildingEvent>>()
+            .send(PlaceBuildingEvent {
+                kind: "shipyard".to_string(),
+                team: Team::PLAYER,
+                position: Vec3::new(CELL_SIZE * 1.5, GROUND_LEVEL, CELL_SIZE * 1.5),
+            });
+        // The shipyard takes fifteen seconds to build.
+        sim.step(920);
+        sim.app
+            .world
+            .resource_mut::<Stockpiles>()
+            .add(Team::PLAYER, 500.);
+        let shipyard = sim.app.world.resource::<Game>().board[1][1]
+            .occupant
+            .unwrap();
+        assert!(sim.app.world.get::<ProductionQueue>(shipyard).is_some());
+        let before = sim.app.world.resource::<Stockpiles>().get(Team::PLAYER);
+        let rally = Vec3::new(CELL_SIZE * 2.5, GROUND_LEVEL, CELL_SIZE * 1.5);
+
+        sim.command(vec![shipyard], UnitCommand::Move(rally));
+        sim.command(vec![shipyard], UnitCommand::Produce("ship".to_string()));
+        sim.command(vec![shipyard], UnitCommand::Produce("ship".to_string()));
+        sim.step(1);
+        assert_eq!(
+            sim.app.world.resource::<Stockpiles>().get(Team::PLAYER),
+            before - 100.
+        );
+        sim.command(vec![shipyard], UnitCommand::CancelProduction);
+        sim.step(1);
+        assert_eq!(
+            sim.app.world.resource::<Stockpiles>().get(Team::PLAYER),
+            before - 50.
+        );
+        assert_eq!(
+            sim.app.world.get::<RallyPoint>(shipyard),
+            Some(&RallyPoint(rally))
+        );
+
+        // A ship takes five seconds.
+        sim.step(310);
+
+        let ships: Vec<_> = sim
+            .app
+            .world
+            .query::<(&UnitType, &Order)>()
+            .iter(&sim.app.world)
+            .filter(|(unit_type, _)| unit_type.0 == "ship")
+            .map(|(_, order)| order.clone())
+            .collect();
+        assert_eq!(ships, [Order::Move(rally)]);
+        assert!(sim
+            .app
+            .world
+            .get::<ProductionQueue>(shipyard)
+            .unwrap()
+            .items
+            .is_empty());
+    }
+
+    #[test]
+    fn enemies_out_of_sight_are_fogged_until_approached() {
+        let mut sim = SimulationHarness::new();
+        let scout = sim.spawn_unit(3., 3.);
+        let enemy = sim.spawn_unit(20., 3.);
+        sim.app.world.entity_mut(enemy).insert(Team(1));
+
+        sim.step(2);
+        assert!(sim.app.world.get::<Fogged>(enemy).is_some());
+
+        sim.command(
+            vec![scout],
+            UnitCommand::Move(Vec3::new(16., GROUND_LEVEL, 3.)),
+        );
+        sim.step(600);
+
+        assert!(sim.app.world.get::<Fogged>(enemy).is_none());
+        let fog = sim.app.world.resource::<FogOfWar>();
+        let start = Vec3::new(3., GROUND_LEVEL, 3.);
+        assert_eq!(
+            fog.vision(Team::PLAYER).unwrap().state_at(start),
+            FogState::Explored
+        );
+    }
+
+    #[test]
+    fn physics_unit_keeps_pace_with_a_kinematic_one() {
+        let mut sim = SimulationHarness::with_physics();
+        let kinematic = sim.spawn_unit(5., 5.);
+        let physics = sim.spawn_physics_unit(5., 15.);
+
+        sim.command(
+            vec![kinematic],
+            UnitCommand::Move(Vec3::new(15., GROUND_LEVEL, 5.)),
+        );
+        sim.command(
+            vec![physics],
+            UnitCommand::Move(Vec3::new(15., GROUND_LEVEL, 15.)),
+        );
+        sim.step(30);
+
+        let kinematic_travelled = sim.position(kinematic).x - 5.;
+        let physics_travelled = sim.position(physics).x - 5.;
+        assert!(kinematic_travelled > 1.);
+        assert!((physics_travelled - kinematic_travelled).abs() < kinematic_travelled * 0.25);
+
+        sim.step(300);
+        assert!(sim.app.world.get::<Destination>(kinematic).is_none());
+        assert!(sim.app.world.get::<Destination>(physics).is_none());
+    }
+
+    #[test]
+    fn overlapping_physics_units_are_pushed_apart() {
+        let mut sim = SimulationHarness::with_physics();
+        let first = sim.spawn_physics_unit(10., 10.);
+        let second = sim.spawn_physics_unit(10.3, 10.);
+
+        sim.step(60);
+
+        let gap = sim.position(first).distance(sim.position(second));
+        assert!(gap >= 2. * UNIT_COLLIDER_RADIUS * 0.9);
+        assert!((sim.position(first).y - GROUND_LEVEL).abs() < 0.01);
+    }
+
+    #[test]
+    fn groups_crossing_head_on_pass_without_running_into_each_other() {
+        let mut sim = SimulationHarness::new();
+        let lanes = [8., 10., 12.];
+        let west: Vec<_> = lanes.iter().map(|&z| sim.spawn_unit(4., z)).collect();
+        let east: Vec<_> = lanes.iter().map(|&z| sim.spawn_unit(20., z)).collect();
+        for (&unit, &z) in west.iter().zip(&lanes) {
+            sim.command(
+                vec![unit],
+                UnitCommand::Move(Vec3::new(20., GROUND_LEVEL, z)),
+            );
+        }
+        for (&unit, &z) in east.iter().zip(&lanes) {
+            sim.command(
+                vec![unit],
+                UnitCommand::Move(Vec3::new(4., GROUND_LEVEL, z)),
+            );
+        }
+
+        let mut closest = f32::MAX;
+        for _ in 0..900 {
+            sim.step(1);
+            for &a in &west {
+                for &b in &east {
+                    closest = closest.min(sim.position(a).distance(sim.position(b)));
+                }
+            }
+        }
+
+        assert!(closest >= 2. * AVOIDANCE_RADIUS * 0.9);
+        for unit in west.into_iter().chain(east) {
+            assert!(sim.app.world.get::<Destination>(unit).is_none());
+        }
+    }
+
+    #[test]
+    fn unit_that_cannot_reach_its_destination_gives_up_after_detouring() {
+        let mut sim = SimulationHarness::new();
+        let unit = sim.spawn_unit(5., 10.);
+        // Units are kept two units in from the edge of the map.
+        let unreachable = Vec3::new(0.5, GROUND_LEVEL, 10.);
+        let mut failures = sim.app.world.resource::<Events<OrderFailed>>().get_reader();
+        let mut failed = Vec::new();
+        let mut detoured = false;
+
+        sim.command(vec![unit], UnitCommand::Move(unreachable));
+        for _ in 0..900 {
+            sim.step(1);
+            detoured |= sim.app.world.get::<Detour>(unit).is_some();
+            let events = sim.app.world.resource::<Events<OrderFailed>>();
+            failed.extend(failures.iter(events).cloned());
+        }
+
+        assert!(detoured);
+        assert_eq!(
+            failed,
+            [OrderFailed {
+                unit,
+                order: Order::Move(unreachable)
+            }]
+        );
+        assert_eq!(sim.app.world.get::<Order>(unit), Some(&Order::Idle));
+        assert!(sim.app.world.get::<Destination>(unit).is_none());
+    }
+
+    #[test]
+    fn transport_carries_the_nearest_units_it_has_room_for() {
+        let mut sim = SimulationHarness::new();
+        let transport = sim.spawn_unit(10., 10.);
+        sim.app
+            .world
+            .entity_mut(transport)
+            .insert(Transport::new(2));
+        let nearest = sim.spawn_unit(11.5, 10.);
+        let next = sim.spawn_unit(10., 12.5);
+        let left_behind = sim.spawn_unit(7., 10.);
+        let point = Vec3::new(20., GROUND_LEVEL, 10.);
+
+        sim.command(vec![transport], UnitCommand::Load);
+        sim.step(2);
+
+        assert_eq!(
+            sim.app.world.get::<Transport>(transport).unwrap().cargo,
+            [nearest, next]
+        );
+        for passenger in [nearest, next] {
+            assert!(sim.app.world.get::<Loaded>(passenger).is_some());
+            assert!(sim.app.world.get::<Unit>(passenger).is_none());
+        }
+        assert!(sim.app.world.get::<Loaded>(left_behind).is_none());
+
+        sim.command(vec![transport], UnitCommand::Unload(point));
+        sim.step(400);
+
+        assert!(sim
+            .app
+            .world
+            .get::<Transport>(transport)
+            .unwrap()
+            .cargo
+            .is_empty());
+        assert_eq!(sim.app.world.get::<Order>(transport), Some(&Order::Idle));
+        for passenger in [nearest, next] {
+            assert!(sim.app.world.get::<Loaded>(passenger).is_none());
+            assert!(sim.app.world.get::<Unit>(passenger).is_some());
+            assert!(sim.position(passenger).distance(point) < UNLOAD_SPACING * 2.);
+        }
+    }
+
+    #[test]
+    fn garrison_walks_out_to_the_unload_point_and_dies_with_its_building() {
+        let mut sim = SimulationHarness::new();
+        let building = sim
+            .app
+            .world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_xyz(10., GROUND_LEVEL, 10.)),
+                Unit,
+                Health::new(400.),
+                Transport::new(4),
+            ))
+            .id();
+        let first = sim.spawn_unit(12., 10.);
+        let second = sim.spawn_unit(8., 10.);
+        let point = Vec3::new(10., GROUND_LEVEL, 20.);
+
+        sim.command(vec![building], UnitCommand::Load);
+        sim.step(2);
+        sim.command(vec![building], UnitCommand::Unload(point));
+        sim.step(400);
+
+        for passenger in [first, second] {
+            assert!(sim.position(passenger).distance(point) < UNLOAD_SPACING * 2.);
+        }
+
+        sim.command(vec![building], UnitCommand::Load);
+        sim.step(2);
+        assert_eq!(
+            sim.app
+                .world
+                .get::<Transport>(building)
+                .unwrap()
+                .cargo
+                .len(),
+            0
+        );
+
+        sim.command(
+            vec![first],
+            UnitCommand::Move(Vec3::new(10., GROUND_LEVEL, 12.)),
+        );
+        sim.step(300);
+        sim.command(vec![building], UnitCommand::Load);
+        sim.step(2);
+        sim.app.world.despawn(building);
+        sim.step(2);
+
+        assert!(sim.app.world.get_entity(first).is_none());
+        assert!(sim.app.world.get_entity(second).is_some());
+    }
+
+    #[test]
+    fn finished_research_speeds_up_old_and_new_ships_of_its_team() {
+        let mut sim = SimulationHarness::new();
+        sim.step(1);
+        let shipyard = sim
+            .app
+            .world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_xyz(10., GROUND_LEVEL, 10.)),
+                Unit,
+                UnitType("shipyard".to_string()),
+                Building {
+                    kind: "shipyard".to_string(),
+                    cells: vec![],
+                },
+                Team::PLAYER,
+            ))
+            .id();
+        // Far enough apart that the two teams leave each other alone.
+        let spawn_ship = |sim: &mut SimulationHarness, x: f32, z: f32, team: Team| {
+            sim.app
+                .world
+                .spawn((
+                    TransformBundle::from_transform(Transform::from_xyz(x, GROUND_LEVEL, z)),
+                    Unit,
+                    UnitType("ship".to_string()),
+                    team,
+                ))
+                .id()
+        };
+        let ship = spawn_ship(&mut sim, 20., 4., Team::PLAYER);
+        let enemy = spawn_ship(&mut sim, 2., 22., Team(1));
+        sim.app
+            .world
+            .resource_mut::<Stockpiles>()
+            .add(Team::PLAYER, 500.);
+        sim.step(1);
+        let before = sim.app.world.resource::<Stockpiles>().get(Team::PLAYER);
+        let speed = sim
+            .app
+            .world
+            .resource::<UnitDefinitions>()
+            .get("ship")
+            .unwrap()
+            .speed;
+        let engines = "Improved Engines".to_string();
+        let research_time = sim
+            .app
+            .world
+            .resource::<ResearchDefinitions>()
+            .get(&engines)
+            .unwrap()
+            .research_time;
+
+        sim.command(
+            vec![shipyard],
+            UnitCommand::Research("Reinforced Hulls".to_string()),
+        );
+        sim.command(vec![shipyard], UnitCommand::Research(engines.clone()));
+        sim.step(1);
+        assert!(sim.app.world.get::<Researching>(shipyard).is_some());
+        assert_eq!(
+            sim.app.world.resource::<Stockpiles>().get(Team::PLAYER),
+            before - 100.
+        );
+        sim.step((research_time / SIMULATION_TICK) as usize + 5);
+
+        assert!(sim.app.world.get::<Researching>(shipyard).is_none());
+        assert!(sim
+            .app
+            .world
+            .resource::<TeamResearch>()
+            .is_complete(Team::PLAYER, &engines));
+        let newer = spawn_ship(&mut sim, 20., 6., Team::PLAYER);
+        sim.step(1);
+        for unit in [ship, newer] {
+            let value = sim.app.world.get::<MovementSpeed>(unit).unwrap().value;
+            assert!((value - speed * 1.2).abs() < 1e-4);
+        }
+        assert_eq!(
+            sim.app.world.get::<MovementSpeed>(enemy).unwrap().value,
+            speed
+        );
+    }
+
+    #[test]
+    fn aura_slows_units_near_it_until_they_leave() {
+        let mut sim = SimulationHarness::new();
+        let source = sim.spawn_unit(5., 5.);
+        sim.app.world.entity_mut(source).insert(Aura {
+            name: "Tar".to_string(),
+            radius: 4.,
+            stat: Stat::Speed,
+            change: StatChange::Multiply(0.5),
+        });
+        let slowed = sim.spawn_unit(5., 7.);
+        let free = sim.spawn_unit(5., 17.);
+        sim.step(1);
+
+        sim.command(
+            vec![slowed],
+            UnitCommand::Move(Vec3::new(8., GROUND_LEVEL, 7.)),
+        );
+        sim.command(
+            vec![free],
+            UnitCommand::Move(Vec3::new(8., GROUND_LEVEL, 17.)),
+        );
+        sim.step(20);
+        assert!(sim.position(slowed).x < sim.position(free).x - 0.1);
+
+        sim.app
+            .world
+            .get_mut::<Transform>(source)
+            .unwrap()
+            .translation = Vec3::new(20., GROUND_LEVEL, 20.);
+        sim.step((AURA_LINGER / SIMULATION_TICK) as usize + 2);
+        for unit in [slowed, free] {
+            assert!(sim
+                .app
+                .world
+                .get::<StatEffects>(unit)
+                .unwrap()
+                .effects
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn splash_hurts_enemies_around_the_target_less_the_further_out_they_are() {
+        let mut sim = SimulationHarness::new();
+        let attacker = sim.spawn_unit(5., 5.);
+        sim.app.world.entity_mut(attacker).insert((
+            Weapon::new(20., 6., 0.5),
+            Splash {
+                radius: 3.,
+                falloff: 0.5,
+                impulse: 0.,
+            },
+        ));
+        let target = sim.spawn_unit(9., 5.);
+        let bystander = sim.spawn_unit(9., 7.);
+        let distant = sim.spawn_unit(9., 12.);
+        for enemy in [target, bystander, distant] {
+            sim.app.world.entity_mut(enemy).insert(Team(1));
+        }
+        let friend = sim.spawn_unit(9., 4.);
+
+        sim.step(5);
+
+        let health = |unit| sim.app.world.get::<Health>(unit).unwrap().value;
+        assert!(health(target) < health(bystander));
+        assert!(health(bystander) < 100.);
+        assert_eq!(health(distant), 100.);
+        assert_eq!(health(friend), 100.);
+    }
+
+    #[test]
+    fn area_damage_pushes_physics_bodies_away() {
+        let mut sim = SimulationHarness::with_physics();
+        let unit = sim.spawn_physics_unit(10., 10.);
+        sim.step(1);
+
+        sim.app
+            .world
+            .resource_mut::<Events<AreaDamageEvent>>()
+            .send(AreaDamageEvent {
+                center: Vec3::new(9., GROUND_LEVEL, 10.),
+                radius: 3.,
+                damage: 10.,
+                falloff: 0.5,
+                impulse: 10.,
+                source: None,
+                spares: None,
+            });
+        sim.step(30);
+
+        assert!(sim.position(unit).x > 10.5);
+        assert!(sim.app.world.get::<Health>(unit).unwrap().value < 100.);
+    }
+}
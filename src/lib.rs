@@ -0,0 +1,68 @@
+use bevy::app::{PluginGroup, PluginGroupBuilder};
+
+pub use components::game::{Game, GameState, Mechanics};
+use plugins::{
+    ActionsPlugin, AnimationControllerPlugin, BuildingsPlugin, CameraPlugin, CombatPlugin,
+    CommandCardPlugin, EconomyPlugin, EffectsPlugin, ExplosionPlugin, FogOverlayPlugin,
+    MinimapPlugin, MovementPlugin, OverlayPlugin, PlacementPlugin, SelectionPanelPlugin,
+    SelectionPlugin, SimulationPlugin, SpawnerPlugin, TransportPlugin, UiPlugin, VisionPlugin,
+    WorldPlugin,
+};
+
+pub mod components;
+pub mod constants;
+pub mod harness;
+pub mod plugins;
+pub mod systems;
+pub mod units;
+pub mod util;
+
+/// Everything needed to play the sandbox on top of `DefaultPlugins`.
+///
+/// Each plugin can be switched off on its own, e.g.
+/// `RtsSandboxPlugins.build().disable::<UiPlugin>()`.
+pub struct RtsSandboxPlugins;
+
+impl PluginGroup for RtsSandboxPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(SimulationPlugin)
+            .add(MovementPlugin)
+            .add(CombatPlugin)
+            .add(EconomyPlugin)
+            .add(BuildingsPlugin)
+            .add(VisionPlugin)
+            .add(TransportPlugin)
+            .add(ActionsPlugin)
+            .add(WorldPlugin)
+            .add(EffectsPlugin)
+            .add(CameraPlugin)
+            .add(SelectionPlugin)
+            .add(AnimationControllerPlugin)
+            .add(UiPlugin)
+            .add(MinimapPlugin)
+            .add(SelectionPanelPlugin)
+            .add(OverlayPlugin)
+            .add(FogOverlayPlugin)
+            .add(ExplosionPlugin)
+            .add(CommandCardPlugin)
+            .add(PlacementPlugin)
+            .add(SpawnerPlugin)
+    }
+}
+
+/// The gameplay subset of `RtsSandboxPlugins` that runs under `MinimalPlugins`.
+pub struct HeadlessPlugins;
+
+impl PluginGroup for HeadlessPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(SimulationPlugin)
+            .add(MovementPlugin)
+            .add(CombatPlugin)
+            .add(EconomyPlugin)
+            .add(BuildingsPlugin)
+            .add(VisionPlugin)
+            .add(TransportPlugin)
+    }
+}
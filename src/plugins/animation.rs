@@ -1,4 +1,6 @@
-use crate::*;
+use bevy::prelude::*;
+
+use crate::GameState;
 
 pub struct AnimationControllerPlugin;
 
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{buildings::BuildingDefinitions, research::ResearchDefinitions},
+    constants::economy::{BUILDINGS_FILE, RESEARCH_FILE},
+    systems::{
+        buildings::{
+            construction_system, place_buildings_system, release_cells_system, PlaceBuildingEvent,
+        },
+        production::{production_commands_system, production_system},
+        research::{research_commands_system, research_system, upgrade_units_system},
+    },
+    GameState,
+};
+
+/// Placing buildings on the board, paying for them and constructing them, and
+/// the production queues and research of finished ones.
+/// Runs headless; `PlacementPlugin` adds the ghost preview and meshes.
+pub struct BuildingsPlugin;
+
+impl Plugin for BuildingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BuildingDefinitions::load_or_default(BUILDINGS_FILE))
+            .insert_resource(ResearchDefinitions::load_or_default(RESEARCH_FILE))
+            .add_event::<PlaceBuildingEvent>()
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    // Spawning is deferred, so free dead occupants before reserving new ones.
+                    .with_system(release_cells_system.before(place_buildings_system))
+                    .with_system(place_buildings_system)
+                    .with_system(construction_system)
+                    .with_system(production_commands_system)
+                    .with_system(production_system.after(production_commands_system))
+                    .with_system(research_commands_system)
+                    .with_system(research_system.after(research_commands_system))
+                    .with_system(upgrade_units_system.after(research_system)),
+            );
+    }
+}
@@ -0,0 +1,317 @@
+use bevy::{
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+use bevy_iso3d_rts_cursor_plugin::RayReflector;
+use bevy_mod_raycast::RaycastSource;
+
+use crate::{
+    components::input::{Action, ActionState},
+    constants::{camera::*, constants::*, plane::*},
+    util::{keep_in_bounds, map_value_to_range},
+    GameState,
+};
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CameraFocusEvent>()
+            .add_startup_system(setup_cameras)
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(camera_controls)
+                    .with_system(camera_zoom)
+                    .with_system(camera_edge_scroll)
+                    .with_system(camera_drag)
+                    .with_system(camera_focus)
+                    .with_system(
+                        smooth_camera
+                            .after(camera_controls)
+                            .after(camera_zoom)
+                            .after(camera_edge_scroll)
+                            .after(camera_drag)
+                            .after(camera_focus),
+                    ),
+            );
+    }
+}
+
+/// Where the camera is heading. Input moves the `target_*` values, which are
+/// clamped to the map, and `smooth_camera` eases the actual values toward them.
+#[derive(Component, Debug, Clone)]
+pub struct RtsCamera {
+    pub focus: Vec3,
+    pub yaw: f32,
+    pub height: f32,
+    pub target_focus: Vec3,
+    pub target_yaw: f32,
+    pub target_height: f32,
+}
+
+impl RtsCamera {
+    pub fn new(focus: Vec3, yaw: f32, height: f32) -> Self {
+        Self {
+            focus,
+            yaw,
+            height,
+            target_focus: focus,
+            target_yaw: yaw,
+            target_height: height,
+        }
+    }
+
+    /// Ground-plane direction the camera is looking in.
+    pub fn forward(&self) -> Vec3 {
+        Vec3::new(-self.target_yaw.sin(), 0., -self.target_yaw.cos())
+    }
+
+    pub fn right(&self) -> Vec3 {
+        Vec3::new(self.target_yaw.cos(), 0., -self.target_yaw.sin())
+    }
+
+    pub fn pan(&mut self, offset: Vec3) {
+        self.target_focus = keep_in_bounds(GAME_BOUNDS, self.target_focus + offset, 0.);
+    }
+
+    /// Pan speed grows with height so the screen scrolls at a similar rate at
+    /// every zoom level.
+    pub fn pan_scale(&self) -> f32 {
+        self.target_height / CAMERA_START_HEIGHT
+    }
+}
+
+/// Moves the camera to look at a point, e.g. a control group.
+pub struct CameraFocusEvent(pub Vec3);
+
+fn setup_cameras(mut commands: Commands) {
+    let rts_camera = RtsCamera::new(
+        Vec3::new(
+            (GAME_X_MIN + GAME_X_MAX) / 2.,
+            GROUND_LEVEL,
+            (GAME_Z_MIN + GAME_Z_MAX) / 2.,
+        ),
+        CAMERA_START_YAW_DEGREES.to_radians(),
+        CAMERA_START_HEIGHT,
+    );
+
+    // Set camera
+    commands
+        .spawn((
+            Camera3dBundle {
+                transform: camera_transform(rts_camera.focus, rts_camera.yaw, rts_camera.height),
+                ..default()
+            },
+            rts_camera,
+        ))
+        .insert(RaycastSource::<RayReflector>::new()); // Designate the camera as our source;
+}
+
+pub fn pitch_for_height(height: f32) -> f32 {
+    let height = height.clamp(CAMERA_MIN_HEIGHT, CAMERA_MAX_HEIGHT);
+    map_value_to_range(
+        height,
+        CAMERA_MIN_HEIGHT,
+        CAMERA_MAX_HEIGHT,
+        CAMERA_MIN_PITCH_DEGREES,
+        CAMERA_MAX_PITCH_DEGREES,
+    )
+    .to_radians()
+}
+
+/// Places the camera `height` above `focus`, behind it along `yaw`, tilted down
+/// by the pitch for that height.
+pub fn camera_transform(focus: Vec3, yaw: f32, height: f32) -> Transform {
+    let back = Vec3::new(yaw.sin(), 0., yaw.cos());
+    let ground_distance = height / pitch_for_height(height).tan();
+    let eye = focus + back * ground_distance + Vec3::Y * height;
+    Transform::from_translation(eye).looking_at(focus, Vec3::Y)
+}
+
+/// Which way to scroll when the cursor is within `margin` pixels of a window
+/// edge. The cursor position has its origin at the bottom left.
+pub fn edge_scroll_direction(cursor: Vec2, width: f32, height: f32, margin: f32) -> Vec2 {
+    let mut direction = Vec2::ZERO;
+    if cursor.x < margin {
+        direction.x -= 1.;
+    }
+    if cursor.x > width - margin {
+        direction.x += 1.;
+    }
+    if cursor.y < margin {
+        direction.y -= 1.;
+    }
+    if cursor.y > height - margin {
+        direction.y += 1.;
+    }
+    direction
+}
+
+fn camera_controls(actions: Res<ActionState>, mut cameras: Query<&mut RtsCamera>, time: Res<Time>) {
+    for mut camera in &mut cameras {
+        let speed = CAMERA_MOVEMENT_SPEED * camera.pan_scale() * time.delta_seconds();
+        let rotate_speed = CAMERA_ROTATION_SPEED * time.delta_seconds();
+
+        let mut offset = Vec3::ZERO;
+        if actions.pressed(Action::CameraForward) {
+            offset += camera.forward();
+        }
+        if actions.pressed(Action::CameraBack) {
+            offset -= camera.forward();
+        }
+        if actions.pressed(Action::CameraLeft) {
+            offset -= camera.right();
+        }
+        if actions.pressed(Action::CameraRight) {
+            offset += camera.right();
+        }
+        if offset != Vec3::ZERO {
+            camera.pan(offset.normalize() * speed);
+        }
+
+        if actions.pressed(Action::CameraRotateLeft) {
+            camera.target_yaw += rotate_speed;
+        }
+        if actions.pressed(Action::CameraRotateRight) {
+            camera.target_yaw -= rotate_speed;
+        }
+    }
+}
+
+fn camera_zoom(mut scroll_events: EventReader<MouseWheel>, mut cameras: Query<&mut RtsCamera>) {
+    let scroll: f32 = scroll_events
+        .iter()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 20.,
+        })
+        .sum();
+
+    if scroll == 0. {
+        return;
+    }
+
+    for mut camera in &mut cameras {
+        camera.target_height = (camera.target_height - scroll * CAMERA_ZOOM_SPEED)
+            .clamp(CAMERA_MIN_HEIGHT, CAMERA_MAX_HEIGHT);
+    }
+}
+
+fn camera_edge_scroll(windows: Res<Windows>, mut cameras: Query<&mut RtsCamera>, time: Res<Time>) {
+    let Some(window) = windows.get_primary() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+
+    let direction = edge_scroll_direction(
+        cursor,
+        window.width(),
+        window.height(),
+        CAMERA_EDGE_SCROLL_MARGIN,
+    );
+    if direction == Vec2::ZERO {
+        return;
+    }
+
+    for mut camera in &mut cameras {
+        let speed = CAMERA_MOVEMENT_SPEED * camera.pan_scale() * time.delta_seconds();
+        let offset = camera.right() * direction.x + camera.forward() * direction.y;
+        camera.pan(offset.normalize() * speed);
+    }
+}
+
+fn camera_drag(
+    actions: Res<ActionState>,
+    mut motion_events: EventReader<MouseMotion>,
+    mut cameras: Query<&mut RtsCamera>,
+) {
+    let delta: Vec2 = motion_events.iter().map(|event| event.delta).sum();
+    if !actions.pressed(Action::CameraDrag) || delta == Vec2::ZERO {
+        return;
+    }
+
+    for mut camera in &mut cameras {
+        // Grab the ground: dragging right pulls the map right, so the focus goes left.
+        let speed = CAMERA_DRAG_SPEED * CAMERA_MOVEMENT_SPEED * camera.pan_scale();
+        let offset = (camera.forward() * delta.y - camera.right() * delta.x) * speed;
+        camera.pan(offset);
+    }
+}
+
+fn camera_focus(
+    mut focus_events: EventReader<CameraFocusEvent>,
+    mut cameras: Query<&mut RtsCamera>,
+) {
+    if let Some(CameraFocusEvent(point)) = focus_events.iter().last() {
+        for mut camera in &mut cameras {
+            camera.target_focus = keep_in_bounds(GAME_BOUNDS, *point, 0.);
+        }
+    }
+}
+
+fn smooth_camera(mut cameras: Query<(&mut Transform, &mut RtsCamera)>, time: Res<Time>) {
+    let blend = 1. - (-CAMERA_SMOOTHING * time.delta_seconds()).exp();
+    for (mut transform, mut camera) in &mut cameras {
+        camera.focus = camera.focus.lerp(camera.target_focus, blend);
+        camera.yaw += (camera.target_yaw - camera.yaw) * blend;
+        camera.height += (camera.target_height - camera.height) * blend;
+        *transform = camera_transform(camera.focus, camera.yaw, camera.height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{camera_transform, edge_scroll_direction, pitch_for_height, RtsCamera};
+    use crate::constants::{
+        camera::{CAMERA_MAX_HEIGHT, CAMERA_MAX_PITCH_DEGREES, CAMERA_MIN_PITCH_DEGREES},
+        plane::{GAME_X_MAX, GAME_Z_MIN},
+    };
+
+    #[test]
+    fn camera_looks_at_its_focus() {
+        let focus = Vec3::new(12., 7.75, 12.);
+        let transform = camera_transform(focus, 0.8, 20.);
+
+        let to_focus = (focus - transform.translation).normalize();
+
+        assert!(transform.forward().abs_diff_eq(to_focus, 0.001));
+        assert!((transform.translation.y - focus.y - 20.).abs() < 0.001);
+    }
+
+    #[test]
+    fn pitch_is_clamped_beyond_zoom_limits() {
+        assert_eq!(pitch_for_height(0.), CAMERA_MIN_PITCH_DEGREES.to_radians());
+        assert_eq!(
+            pitch_for_height(CAMERA_MAX_HEIGHT * 10.),
+            CAMERA_MAX_PITCH_DEGREES.to_radians()
+        );
+    }
+
+    #[test]
+    fn pan_is_clamped_to_game_bounds() {
+        let mut camera = RtsCamera::new(Vec3::new(12., 7.75, 12.), 0., 20.);
+
+        camera.pan(Vec3::new(100., 0., -100.));
+
+        assert_eq!(camera.target_focus.x, GAME_X_MAX);
+        assert_eq!(camera.target_focus.z, GAME_Z_MIN);
+    }
+
+    #[test]
+    fn cursor_near_top_left_scrolls_forward_and_left() {
+        let direction = edge_scroll_direction(Vec2::new(2., 478.), 640., 480., 12.);
+
+        assert_eq!(direction, Vec2::new(-1., 1.));
+    }
+
+    #[test]
+    fn cursor_in_middle_does_not_scroll() {
+        let direction = edge_scroll_direction(Vec2::new(320., 240.), 640., 480., 12.);
+
+        assert_eq!(direction, Vec2::ZERO);
+    }
+}
@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+
+use crate::{
+    systems::{
+        combat::{
+            apply_damage_system, area_damage_system, death_system, weapon_system, AreaDamageEvent,
+            DamageEvent,
+        },
+        guard::guard_system,
+    },
+    GameState,
+};
+
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>()
+            .add_event::<AreaDamageEvent>()
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(guard_system.before(weapon_system))
+                    .with_system(weapon_system.before(area_damage_system))
+                    .with_system(area_damage_system.before(apply_damage_system))
+                    .with_system(apply_damage_system)
+                    .with_system(death_system.after(apply_damage_system)),
+            );
+    }
+}
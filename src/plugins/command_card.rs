@@ -0,0 +1,922 @@
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+use bevy_iso3d_rts_cursor_plugin::Cursor;
+
+use crate::{
+    components::{
+        buildings::{Building, BuildingDefinitions, ProductionQueue, UnderConstruction},
+        economy::{Harvester, ResourceNode},
+        input::{Action, ActionState, CardKey, InputBindings, InputKind, Modifiers},
+        mechanics::{AbilityCooldowns, MovementSpeed, Selected, Team, Unit, UnitType, Weapon},
+        orders::OrderKind,
+        research::{ResearchDefinitions, TeamResearch},
+        transport::Transport,
+        vision::Fogged,
+    },
+    constants::{economy::RESOURCE_NODE_CLICK_RADIUS, hud::*, selection::CLICK_SELECT_RADIUS},
+    plugins::minimap::{cursor_on_minimap, minimap_to_world},
+    systems::{
+        buildings::PlaceBuildingEvent,
+        commands::{CommandEvent, UnitCommand},
+        selection::SelectionChanged,
+    },
+    units::definitions::UnitDefinitions,
+    util::ground_distance,
+    GameState,
+};
+
+/// The bottom-right grid of order and ability buttons for the selection. Each
+/// button also has a bindable hotkey; both send the same `CommandEvent`s as
+/// right-click and the minimap.
+pub struct CommandCardPlugin;
+
+impl Plugin for CommandCardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CommandTargeting>()
+            .add_startup_system(setup_command_card)
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(rebuild_command_card)
+                    .with_system(command_card_input.after(rebuild_command_card))
+                    .with_system(world_click_system.after(command_card_input))
+                    .with_system(update_command_buttons)
+                    .with_system(update_cooldown_overlays),
+            );
+    }
+}
+
+/// What the next click on the map is for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Targeting {
+    Order(OrderKind),
+    /// Another point for the patrol just given.
+    PatrolPoint,
+    /// Where to put a building of this kind.
+    Build(String),
+}
+
+impl Targeting {
+    pub fn label(&self) -> &str {
+        match self {
+            Targeting::Order(kind) => kind.label(),
+            Targeting::PatrolPoint => OrderKind::Patrol.label(),
+            Targeting::Build(kind) => kind,
+        }
+    }
+
+    pub fn prompt(&self) -> String {
+        match self {
+            Targeting::PatrolPoint => format!(
+                "{}: click to add another point, right-click when done.",
+                self.label()
+            ),
+            _ => format!("{}: click a target, right-click to cancel.", self.label()),
+        }
+    }
+}
+
+/// An order or building that waits for the player to click where or whom it
+/// applies to.
+#[derive(Resource, Default, Debug)]
+pub struct CommandTargeting(pub Option<Targeting>);
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CardButton {
+    Order(OrderKind),
+    Ability(String),
+    Produce(String),
+    CancelProduction,
+    Research(String),
+    Build(String),
+}
+
+#[derive(Component)]
+pub struct CommandCard;
+
+#[derive(Component)]
+pub struct CommandButton {
+    pub button: CardButton,
+    /// The action that presses it from the keyboard or a gamepad.
+    pub hotkey: Option<Action>,
+    pub tooltip: String,
+}
+
+#[derive(Component)]
+pub struct CooldownOverlay {
+    pub ability: String,
+}
+
+#[derive(Component)]
+pub struct CommandTooltip;
+
+/// What one selected unit can do, as far as the command card cares.
+pub struct UnitCapabilities<'a> {
+    pub unit_type: Option<&'a UnitType>,
+    pub mobile: bool,
+    pub armed: bool,
+    pub harvester: bool,
+    /// A finished building with a production queue.
+    pub producer: bool,
+    /// A transport, or a finished building units can garrison.
+    pub transport: bool,
+    /// A building that is done being built.
+    pub built: bool,
+}
+
+/// The buttons to show for a selection: the orders any selected unit can
+/// carry out, then every ability of the selected unit types by name, then what
+/// selected buildings produce, then what they research, then the buildings
+/// harvesters can put up.
+pub fn available_buttons<'a>(
+    units: impl IntoIterator<Item = UnitCapabilities<'a>>,
+    definitions: &UnitDefinitions,
+    buildings: &BuildingDefinitions,
+    research: &ResearchDefinitions,
+) -> Vec<CardButton> {
+    let (mut mobile, mut armed, mut harvester, mut transport) = (false, false, false, false);
+    let mut abilities = BTreeSet::new();
+    let mut products = BTreeSet::new();
+    let mut researchable = BTreeSet::new();
+    for unit in units {
+        mobile |= unit.mobile;
+        armed |= unit.armed;
+        harvester |= unit.harvester;
+        transport |= unit.transport;
+        if let Some(building) = unit
+            .unit_type
+            .filter(|_| unit.producer)
+            .and_then(|t| buildings.get(&t.0))
+        {
+            products.extend(building.produces.iter().map(|option| option.unit.clone()));
+        }
+        if let Some(building) = unit.unit_type.filter(|_| unit.built) {
+            researchable.extend(research.available_at(&building.0));
+        }
+        let Some(definition) = unit.unit_type.and_then(|t| definitions.get(&t.0)) else {
+            continue;
+        };
+        abilities.extend(definition.abilities.iter().map(|a| a.name.clone()));
+    }
+
+    let producing = !products.is_empty();
+    OrderKind::ALL
+        .into_iter()
+        .filter(|kind| match kind {
+            OrderKind::Move | OrderKind::Patrol | OrderKind::Guard => mobile,
+            OrderKind::Attack => armed,
+            OrderKind::Gather => harvester,
+            OrderKind::Stop | OrderKind::Hold => mobile || armed,
+            OrderKind::Load | OrderKind::Unload => transport,
+        })
+        .map(CardButton::Order)
+        .chain(abilities.into_iter().map(CardButton::Ability))
+        .chain(products.into_iter().map(CardButton::Produce))
+        .chain(producing.then_some(CardButton::CancelProduction))
+        .chain(researchable.into_iter().map(CardButton::Research))
+        .chain(
+            buildings
+                .buildings
+                .keys()
+                .filter(|_| harvester)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .cloned()
+                .map(CardButton::Build),
+        )
+        .collect()
+}
+
+fn key_binding(bindings: &InputBindings, action: Action) -> Option<KeyCode> {
+    bindings.bindings.get(&action)?.iter().find_map(|binding| {
+        match (binding.input, binding.modifiers) {
+            (InputKind::Key(key), Modifiers::NONE) => Some(key),
+            _ => None,
+        }
+    })
+}
+
+fn setup_command_card(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    right: Val::Px(HUD_MARGIN),
+                    bottom: Val::Px(HUD_MARGIN),
+                    ..default()
+                },
+                size: Size::new(Val::Px(COMMAND_CARD_WIDTH), Val::Px(SELECTION_PANEL_HEIGHT)),
+                flex_direction: FlexDirection::Row,
+                flex_wrap: FlexWrap::Wrap,
+                align_content: AlignContent::FlexStart,
+                padding: UiRect::all(Val::Px(4.)),
+                ..default()
+            },
+            background_color: HUD_BACKGROUND_COLOR.into(),
+            ..default()
+        },
+        // Lets clicks on the card's background be told apart from clicks on the map.
+        Interaction::default(),
+        CommandCard,
+        Name::new("CommandCard"),
+    ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load(HUD_FONT),
+                font_size: HUD_FONT_SIZE,
+                color: HUD_TEXT_COLOR,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                right: Val::Px(HUD_MARGIN),
+                bottom: Val::Px(HUD_MARGIN * 1.5 + SELECTION_PANEL_HEIGHT),
+                ..default()
+            },
+            max_size: Size::new(Val::Px(COMMAND_CARD_WIDTH * 1.5), Val::Auto),
+            ..default()
+        }),
+        CommandTooltip,
+        Name::new("CommandTooltip"),
+    ));
+}
+
+/// Rebuilds the card when the selection changes, when a selected building
+/// finishes and can start producing or take in a garrison, or when research
+/// finishes and its button goes away.
+fn rebuild_command_card(
+    mut commands: Commands,
+    mut selection_changed: EventReader<SelectionChanged>,
+    finished: Query<
+        (),
+        (
+            Or<(Added<ProductionQueue>, Added<Transport>)>,
+            With<Selected>,
+        ),
+    >,
+    current: Query<Entity, With<Selected>>,
+    mut targeting: ResMut<CommandTargeting>,
+    asset_server: Res<AssetServer>,
+    definitions: Res<UnitDefinitions>,
+    buildings: Res<BuildingDefinitions>,
+    research_definitions: Res<ResearchDefinitions>,
+    research: Res<TeamResearch>,
+    bindings: Res<InputBindings>,
+    cards: Query<Entity, With<CommandCard>>,
+    units: Query<
+        (
+            Option<&UnitType>,
+            Option<&MovementSpeed>,
+            Option<&Weapon>,
+            Option<&Harvester>,
+            Option<&ProductionQueue>,
+            Option<&Transport>,
+            Option<&Building>,
+            Option<&UnderConstruction>,
+        ),
+        With<Unit>,
+    >,
+) {
+    let selected = match selection_changed.iter().last() {
+        Some(SelectionChanged { selected }) => selected.clone(),
+        None if !finished.is_empty() || (research.is_changed() && !current.is_empty()) => {
+            current.iter().collect()
+        }
+        None => return,
+    };
+    let Ok(card) = cards.get_single() else {
+        return;
+    };
+    commands.entity(card).despawn_descendants();
+    targeting.0 = None;
+
+    let selected_types: Vec<&UnitType> = units
+        .iter_many(&selected)
+        .filter_map(|(unit_type, ..)| unit_type)
+        .collect();
+    let mut buttons = available_buttons(
+        units.iter_many(&selected).map(
+            |(unit_type, speed, weapon, harvester, queue, transport, building, construction)| {
+                UnitCapabilities {
+                    unit_type,
+                    mobile: speed.is_some(),
+                    armed: weapon.is_some(),
+                    harvester: harvester.is_some(),
+                    producer: queue.is_some(),
+                    transport: transport.is_some(),
+                    built: building.is_some() && construction.is_none(),
+                }
+            },
+        ),
+        &definitions,
+        &buildings,
+        &research_definitions,
+    );
+    buttons.retain(|button| match button {
+        CardButton::Research(name) => !research.is_complete(Team::PLAYER, name),
+        _ => true,
+    });
+
+    let style = TextStyle {
+        font: asset_server.load(HUD_FONT),
+        font_size: HUD_FONT_SIZE - 2.,
+        color: HUD_TEXT_COLOR,
+    };
+    let mut slots = 0..;
+    commands.entity(card).with_children(|card| {
+        for button in buttons {
+            let hotkey = match &button {
+                CardButton::Order(kind) => Some(CardKey::Order(*kind)),
+                CardButton::CancelProduction => Some(CardKey::Cancel),
+                _ => slots.next().map(CardKey::Slot),
+            }
+            .map(Action::CommandCard);
+            let (label, tooltip) = match &button {
+                CardButton::Order(kind) => (kind.label().to_string(), kind.tooltip().to_string()),
+                CardButton::Ability(name) => {
+                    let ability = selected_types
+                        .iter()
+                        .find_map(|unit_type| definitions.ability(&unit_type.0, name));
+                    (
+                        name.clone(),
+                        ability.map_or_else(String::new, |ability| ability.tooltip.clone()),
+                    )
+                }
+                CardButton::Produce(unit) => {
+                    let option = selected_types
+                        .iter()
+                        .find_map(|building| buildings.production(&building.0, unit));
+                    (
+                        unit.clone(),
+                        option.map_or_else(String::new, |option| {
+                            format!(
+                                "Produces a {} in {:.0}s. Costs {:.0}.",
+                                unit, option.build_time, option.cost
+                            )
+                        }),
+                    )
+                }
+                CardButton::CancelProduction => (
+                    "Cancel".to_string(),
+                    "Removes the last queued unit and refunds it.".to_string(),
+                ),
+                CardButton::Research(name) => {
+                    let definition = research_definitions.get(name);
+                    (
+                        name.clone(),
+                        definition.map_or_else(String::new, |definition| {
+                            format!(
+                                "{} Costs {:.0}, takes {:.0}s.",
+                                definition.tooltip, definition.cost, definition.research_time
+                            )
+                        }),
+                    )
+                }
+                CardButton::Build(kind) => {
+                    let building = buildings.get(kind);
+                    (
+                        kind.clone(),
+                        building.map_or_else(String::new, |building| {
+                            format!("{} Costs {:.0}.", building.tooltip, building.cost)
+                        }),
+                    )
+                }
+            };
+            let text = match hotkey.and_then(|action| key_binding(&bindings, action)) {
+                Some(key) => format!("{}\n[{:?}]", label, key),
+                None => label,
+            };
+            let ability = match &button {
+                CardButton::Ability(name) => Some(name.clone()),
+                _ => None,
+            };
+
+            card.spawn((
+                ButtonBundle {
+                    style: Style {
+                        size: Size::new(
+                            Val::Px(COMMAND_BUTTON_WIDTH),
+                            Val::Px(COMMAND_BUTTON_HEIGHT),
+                        ),
+                        margin: UiRect::all(Val::Px(COMMAND_BUTTON_MARGIN)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: COMMAND_BUTTON_COLOR.into(),
+                    ..default()
+                },
+                CommandButton {
+                    button,
+                    hotkey,
+                    tooltip,
+                },
+            ))
+            .with_children(|button| {
+                button.spawn(
+                    TextBundle::from_section(text, style.clone())
+                        .with_text_alignment(TextAlignment::CENTER),
+                );
+                if let Some(ability) = ability {
+                    button.spawn((
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                position: UiRect {
+                                    left: Val::Px(0.),
+                                    bottom: Val::Px(0.),
+                                    ..default()
+                                },
+                                size: Size::new(Val::Percent(100.), Val::Percent(0.)),
+                                ..default()
+                            },
+                            background_color: COMMAND_COOLDOWN_COLOR.into(),
+                            ..default()
+                        },
+                        CooldownOverlay { ability },
+                    ));
+                }
+            });
+        }
+    });
+}
+
+/// Clicks and hotkeys both end up here. Orders that need a target wait for
+/// the next click on the map; the rest are sent straight away.
+fn command_card_input(
+    actions: Res<ActionState>,
+    mut targeting: ResMut<CommandTargeting>,
+    buttons: Query<(&Interaction, &CommandButton)>,
+    clicked: Query<(&Interaction, &CommandButton), Changed<Interaction>>,
+    selected: Query<Entity, (With<Selected>, With<Unit>)>,
+    mut command_events: EventWriter<CommandEvent>,
+) {
+    let mut pressed: Vec<&CardButton> = clicked
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Clicked)
+        .map(|(_, button)| &button.button)
+        .collect();
+    for (_, button) in &buttons {
+        if button
+            .hotkey
+            .map_or(false, |action| actions.just_pressed(action))
+        {
+            pressed.push(&button.button);
+        }
+    }
+
+    for button in pressed {
+        let command = match button {
+            CardButton::Order(kind) if kind.needs_target() => {
+                targeting.0 = Some(Targeting::Order(*kind));
+                continue;
+            }
+            CardButton::Order(OrderKind::Stop) => UnitCommand::Stop,
+            CardButton::Order(OrderKind::Hold) => UnitCommand::Hold,
+            CardButton::Order(OrderKind::Load) => UnitCommand::Load,
+            // Waiting for a target, above.
+            CardButton::Order(
+                OrderKind::Move
+                | OrderKind::Attack
+                | OrderKind::Patrol
+                | OrderKind::Guard
+                | OrderKind::Gather
+                | OrderKind::Unload,
+            ) => continue,
+            CardButton::Build(kind) => {
+                targeting.0 = Some(Targeting::Build(kind.clone()));
+                continue;
+            }
+            CardButton::Ability(name) => UnitCommand::Ability(name.clone()),
+            CardButton::Produce(unit) => UnitCommand::Produce(unit.clone()),
+            CardButton::CancelProduction => UnitCommand::CancelProduction,
+            CardButton::Research(name) => UnitCommand::Research(name.clone()),
+        };
+        targeting.0 = None;
+        command_events.send(CommandEvent {
+            units: selected.iter().collect(),
+            command,
+        });
+    }
+}
+
+fn nearest_within(
+    point: Vec3,
+    radius: f32,
+    candidates: impl Iterator<Item = (Entity, Vec3)>,
+) -> Option<Entity> {
+    candidates
+        .map(|(entity, position)| (entity, ground_distance(point, position)))
+        .filter(|(_, distance)| *distance < radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entity, _)| entity)
+}
+
+/// Left-click gives the order waiting for a target, or places the building
+/// waiting for a spot, and right-click cancels it. Without one, right-click
+/// attacks the enemy under the cursor, gathers from the resource node there,
+/// or moves there. Clicks on the HUD are left to the HUD. After a patrol is
+/// given, further left-clicks add points to it until right-click.
+fn world_click_system(
+    actions: Res<ActionState>,
+    cursor: Res<Cursor>,
+    windows: Res<Windows>,
+    mut targeting: ResMut<CommandTargeting>,
+    hud: Query<&Interaction>,
+    selected: Query<(Entity, Option<&Weapon>, Option<&Harvester>), (With<Selected>, With<Unit>)>,
+    units: Query<(Entity, &GlobalTransform, Option<&Team>), (With<Unit>, Without<Fogged>)>,
+    nodes: Query<(Entity, &GlobalTransform), With<ResourceNode>>,
+    mut command_events: EventWriter<CommandEvent>,
+    mut place_events: EventWriter<PlaceBuildingEvent>,
+) {
+    let select = actions.just_pressed(Action::Select);
+    let command = actions.just_pressed(Action::Command);
+    if !select && !command {
+        return;
+    }
+    if hud
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+    {
+        return;
+    }
+
+    let on_minimap = windows
+        .get_primary()
+        .and_then(|window| window.cursor_position())
+        .and_then(cursor_on_minimap);
+    let point = on_minimap.map_or(cursor.location.xyz, minimap_to_world);
+    let enemy_at_point = || {
+        nearest_within(
+            point,
+            CLICK_SELECT_RADIUS,
+            units
+                .iter()
+                .filter(|(_, _, team)| team.copied().unwrap_or_default() != Team::PLAYER)
+                .map(|(unit, transform, _)| (unit, transform.translation())),
+        )
+    };
+    let friend_at_point = || {
+        nearest_within(
+            point,
+            CLICK_SELECT_RADIUS,
+            units
+                .iter()
+                .filter(|(_, _, team)| team.copied().unwrap_or_default() == Team::PLAYER)
+                .map(|(unit, transform, _)| (unit, transform.translation())),
+        )
+    };
+    let node_at_point = || {
+        nearest_within(
+            point,
+            RESOURCE_NODE_CLICK_RADIUS,
+            nodes
+                .iter()
+                .map(|(node, transform)| (node, transform.translation())),
+        )
+    };
+
+    let unit_command = match targeting.0.take() {
+        Some(_) if command => return,
+        Some(Targeting::Build(kind)) => {
+            place_events.send(PlaceBuildingEvent {
+                kind,
+                team: Team::PLAYER,
+                position: point,
+            });
+            return;
+        }
+        // Attacking the ground attack-moves there.
+        Some(Targeting::Order(OrderKind::Attack)) => match enemy_at_point() {
+            Some(enemy) => UnitCommand::Attack(enemy),
+            None => UnitCommand::AttackMove(point),
+        },
+        Some(Targeting::Order(OrderKind::Patrol)) => {
+            targeting.0 = Some(Targeting::PatrolPoint);
+            UnitCommand::Patrol(point)
+        }
+        Some(Targeting::PatrolPoint) => {
+            targeting.0 = Some(Targeting::PatrolPoint);
+            UnitCommand::AddPatrolPoint(point)
+        }
+        Some(Targeting::Order(OrderKind::Unload)) => UnitCommand::Unload(point),
+        Some(Targeting::Order(OrderKind::Guard)) => {
+            let Some(friend) = friend_at_point() else {
+                return;
+            };
+            UnitCommand::Guard(friend)
+        }
+        Some(Targeting::Order(OrderKind::Gather)) => {
+            let Some(node) = node_at_point() else {
+                return;
+            };
+            UnitCommand::Gather(node)
+        }
+        Some(Targeting::Order(OrderKind::Move)) => UnitCommand::Move(point),
+        // Given straight away, never waiting for a target.
+        Some(Targeting::Order(OrderKind::Stop | OrderKind::Hold | OrderKind::Load)) => return,
+        // The minimap handles its own right-clicks.
+        None if command && on_minimap.is_none() => {
+            let armed = selected.iter().any(|(_, weapon, _)| weapon.is_some());
+            let harvesters = selected.iter().any(|(_, _, harvester)| harvester.is_some());
+            if let Some(enemy) = enemy_at_point().filter(|_| armed) {
+                UnitCommand::Attack(enemy)
+            } else if let Some(node) = node_at_point().filter(|_| harvesters) {
+                UnitCommand::Gather(node)
+            } else {
+                UnitCommand::Move(point)
+            }
+        }
+        None => return,
+    };
+
+    command_events.send(CommandEvent {
+        units: selected.iter().map(|(unit, ..)| unit).collect(),
+        command: unit_command,
+    });
+}
+
+fn update_command_buttons(
+    targeting: Res<CommandTargeting>,
+    mut buttons: Query<(&Interaction, &CommandButton, &mut BackgroundColor)>,
+    mut tooltips: Query<&mut Text, With<CommandTooltip>>,
+) {
+    let mut tooltip = targeting
+        .0
+        .as_ref()
+        .map_or_else(String::new, |targeting| targeting.prompt());
+
+    for (interaction, button, mut color) in &mut buttons {
+        let active = match (&button.button, &targeting.0) {
+            (CardButton::Order(kind), Some(Targeting::Order(targeted))) => kind == targeted,
+            (CardButton::Order(OrderKind::Patrol), Some(Targeting::PatrolPoint)) => true,
+            (CardButton::Build(kind), Some(Targeting::Build(targeted))) => kind == targeted,
+            _ => false,
+        };
+        *color = match interaction {
+            _ if active => COMMAND_BUTTON_ACTIVE_COLOR,
+            Interaction::Hovered | Interaction::Clicked => COMMAND_BUTTON_HOVERED_COLOR,
+            Interaction::None => COMMAND_BUTTON_COLOR,
+        }
+        .into();
+
+        if *interaction == Interaction::Hovered {
+            tooltip = button.tooltip.clone();
+        }
+    }
+
+    for mut text in &mut tooltips {
+        if text.sections[0].value != tooltip {
+            text.sections[0].value = tooltip.clone();
+        }
+    }
+}
+
+/// Covers ability buttons from the bottom up in proportion to the cooldown
+/// left. With several casters the button clears as soon as any is ready.
+fn update_cooldown_overlays(
+    definitions: Res<UnitDefinitions>,
+    mut overlays: Query<(&CooldownOverlay, &mut Style)>,
+    selected: Query<(&UnitType, &AbilityCooldowns), With<Selected>>,
+) {
+    for (CooldownOverlay { ability }, mut style) in &mut overlays {
+        let remaining = selected
+            .iter()
+            .filter(|(unit_type, _)| definitions.ability(&unit_type.0, ability).is_some())
+            .map(|(_, cooldowns)| cooldowns.remaining(ability))
+            .reduce(f32::min)
+            .unwrap_or(0.);
+        style.size.height = Val::Percent(remaining * 100.);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{available_buttons, key_binding, CardButton, UnitCapabilities};
+    use crate::{
+        components::{
+            buildings::BuildingDefinitions,
+            input::{Action, CardKey, InputBindings},
+            mechanics::UnitType,
+            orders::OrderKind,
+            research::ResearchDefinitions,
+        },
+        units::definitions::UnitDefinitions,
+    };
+
+    fn definitions() -> UnitDefinitions {
+        UnitDefinitions::from_ron(include_str!("../../assets/data/units.ron")).unwrap()
+    }
+
+    #[test]
+    fn unarmed_units_get_no_attack_button() {
+        let buttons = available_buttons(
+            [UnitCapabilities {
+                unit_type: None,
+                mobile: true,
+                armed: false,
+                harvester: false,
+                producer: false,
+                transport: false,
+                built: false,
+            }],
+            &definitions(),
+            &BuildingDefinitions::default(),
+            &ResearchDefinitions::default(),
+        );
+
+        assert_eq!(
+            buttons,
+            [
+                OrderKind::Move,
+                OrderKind::Stop,
+                OrderKind::Hold,
+                OrderKind::Patrol,
+                OrderKind::Guard
+            ]
+            .map(CardButton::Order)
+        );
+    }
+
+    #[test]
+    fn abilities_of_a_selection_are_listed_once() {
+        let ship = UnitType("ship".to_string());
+        let ships = (0..3).map(|_| UnitCapabilities {
+            unit_type: Some(&ship),
+            mobile: true,
+            armed: true,
+            harvester: true,
+            producer: false,
+            transport: true,
+            built: false,
+        });
+
+        let buttons = available_buttons(
+            ships,
+            &definitions(),
+            &BuildingDefinitions::default(),
+            &ResearchDefinitions::default(),
+        );
+
+        assert_eq!(buttons.len(), OrderKind::ALL.len() + 1);
+        assert_eq!(
+            buttons.last(),
+            Some(&CardButton::Ability("Repair".to_string()))
+        );
+    }
+
+    #[test]
+    fn harvesters_get_a_button_per_building() {
+        let ship = UnitType("ship".to_string());
+        let buildings =
+            BuildingDefinitions::from_ron(include_str!("../../assets/data/buildings.ron")).unwrap();
+
+        let buttons = available_buttons(
+            [UnitCapabilities {
+                unit_type: Some(&ship),
+                mobile: true,
+                armed: true,
+                harvester: true,
+                producer: false,
+                transport: false,
+                built: false,
+            }],
+            &definitions(),
+            &buildings,
+            &ResearchDefinitions::default(),
+        );
+
+        assert_eq!(
+            &buttons[buttons.len() - 2..],
+            &[
+                CardButton::Build("depot".to_string()),
+                CardButton::Build("shipyard".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn finished_shipyards_offer_their_units_and_a_cancel() {
+        let shipyard = UnitType("shipyard".to_string());
+        let buildings =
+            BuildingDefinitions::from_ron(include_str!("../../assets/data/buildings.ron")).unwrap();
+
+        let buttons = available_buttons(
+            [UnitCapabilities {
+                unit_type: Some(&shipyard),
+                mobile: false,
+                armed: false,
+                harvester: false,
+                producer: true,
+                transport: false,
+                built: true,
+            }],
+            &definitions(),
+            &buildings,
+            &ResearchDefinitions::default(),
+        );
+
+        assert_eq!(
+            buttons,
+            [
+                CardButton::Produce("ship".to_string()),
+                CardButton::CancelProduction
+            ]
+        );
+    }
+
+    #[test]
+    fn built_shipyards_offer_research() {
+        let shipyard = UnitType("shipyard".to_string());
+        let research =
+            ResearchDefinitions::from_ron(include_str!("../../assets/data/research.ron")).unwrap();
+        let capabilities = |built| UnitCapabilities {
+            unit_type: Some(&shipyard),
+            mobile: false,
+            armed: false,
+            harvester: false,
+            producer: false,
+            transport: false,
+            built,
+        };
+
+        let buttons = available_buttons(
+            [capabilities(true)],
+            &definitions(),
+            &BuildingDefinitions::default(),
+            &research,
+        );
+        let unfinished = available_buttons(
+            [capabilities(false)],
+            &definitions(),
+            &BuildingDefinitions::default(),
+            &research,
+        );
+
+        assert_eq!(
+            buttons,
+            research
+                .available_at("shipyard")
+                .into_iter()
+                .map(CardButton::Research)
+                .collect::<Vec<_>>()
+        );
+        assert!(unfinished.is_empty());
+    }
+
+    #[test]
+    fn every_button_from_the_data_gets_a_bound_slot() {
+        let bindings =
+            InputBindings::from_ron(include_str!("../../assets/config/bindings.ron")).unwrap();
+        let buildings =
+            BuildingDefinitions::from_ron(include_str!("../../assets/data/buildings.ron")).unwrap();
+        let research =
+            ResearchDefinitions::from_ron(include_str!("../../assets/data/research.ron")).unwrap();
+        let (ship, shipyard) = (
+            UnitType("ship".to_string()),
+            UnitType("shipyard".to_string()),
+        );
+
+        // A harvester and a finished shipyard show every kind of data button.
+        let buttons = available_buttons(
+            [
+                UnitCapabilities {
+                    unit_type: Some(&ship),
+                    mobile: true,
+                    armed: true,
+                    harvester: true,
+                    producer: false,
+                    transport: true,
+                    built: false,
+                },
+                UnitCapabilities {
+                    unit_type: Some(&shipyard),
+                    mobile: false,
+                    armed: false,
+                    harvester: false,
+                    producer: true,
+                    transport: false,
+                    built: true,
+                },
+            ],
+            &definitions(),
+            &buildings,
+            &research,
+        );
+        let slots = buttons
+            .iter()
+            .filter(|button| !matches!(button, CardButton::Order(_) | CardButton::CancelProduction))
+            .count();
+
+        for slot in 0..slots as u8 {
+            assert!(key_binding(&bindings, Action::CommandCard(CardKey::Slot(slot))).is_some());
+        }
+        assert!(buttons.contains(&CardButton::CancelProduction));
+        assert!(key_binding(&bindings, Action::CommandCard(CardKey::Cancel)).is_some());
+    }
+}
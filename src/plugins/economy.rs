@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::economy::{EconomyConfig, Stockpiles},
+    constants::economy::ECONOMY_FILE,
+    systems::economy::{harvest_system, setup_economy},
+    GameState,
+};
+
+/// Resource nodes, depots, harvesting and each team's stockpile. Runs headless;
+/// `WorldPlugin` gives nodes and depots their meshes.
+pub struct EconomyPlugin;
+
+impl Plugin for EconomyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(EconomyConfig::load_or_default(ECONOMY_FILE))
+            .init_resource::<Stockpiles>()
+            .add_system_set(SystemSet::on_enter(GameState::Playing).with_system(setup_economy))
+            .add_system_set(SystemSet::on_update(GameState::Playing).with_system(harvest_system));
+    }
+}
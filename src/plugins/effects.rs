@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::effects::TweenCompleted,
+    systems::{
+        combat::death_system,
+        effects::{
+            hit_flash_system, instance_effect_materials, selection_flash_system, tween_system,
+        },
+    },
+    GameState,
+};
+
+/// Plays `Tweens` on materials and transforms, and uses them to flash units
+/// that are hit or selected.
+pub struct EffectsPlugin;
+
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TweenCompleted>().add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                // These insert on the units they flash, so their commands have
+                // to land before the despawns of units that died this frame.
+                .with_system(hit_flash_system.before(death_system))
+                .with_system(selection_flash_system.before(death_system))
+                .with_system(
+                    instance_effect_materials
+                        .after(hit_flash_system)
+                        .after(selection_flash_system)
+                        .before(death_system),
+                )
+                .with_system(tween_system.after(instance_effect_materials)),
+        );
+    }
+}
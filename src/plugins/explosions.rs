@@ -0,0 +1,103 @@
+use bevy::{
+    pbr::{NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+};
+
+use crate::{
+    components::{
+        effects::{Easing, EffectMaterials, Tween, TweenProperty, Tweens},
+        mechanics::{Lifetime, Team},
+        vision::FogOfWar,
+    },
+    constants::overlays::*,
+    systems::combat::AreaDamageEvent,
+    GameState,
+};
+
+/// A flash the size of the burst wherever area damage lands in the player's
+/// sight. Each flash has its own material to fade, and goes with its
+/// `Lifetime`.
+pub struct ExplosionPlugin;
+
+impl Plugin for ExplosionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_explosions)
+            .add_system_set(SystemSet::on_update(GameState::Playing).with_system(spawn_explosions));
+    }
+}
+
+#[derive(Resource)]
+pub struct ExplosionMesh(pub Handle<Mesh>);
+
+fn setup_explosions(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    let mesh = meshes.add(Mesh::from(shape::UVSphere {
+        radius: 1.,
+        sectors: 16,
+        stacks: 8,
+    }));
+    commands.insert_resource(ExplosionMesh(mesh));
+}
+
+fn spawn_explosions(
+    mut commands: Commands,
+    mut events: EventReader<AreaDamageEvent>,
+    fog: Res<FogOfWar>,
+    mesh: Res<ExplosionMesh>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for area in events.iter() {
+        if !fog.is_visible(Team::PLAYER, area.center) {
+            continue;
+        }
+        let material = materials.add(StandardMaterial {
+            base_color: EXPLOSION_COLOR,
+            emissive: EXPLOSION_COLOR,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        });
+        let burst = Tweens::new(
+            Tween::new(
+                "fade",
+                TweenProperty::Alpha {
+                    from: EXPLOSION_COLOR.a(),
+                    to: 0.,
+                },
+                EXPLOSION_LIFETIME,
+            )
+            .with_easing(Easing::QuadIn),
+        )
+        .with(
+            Tween::new(
+                "grow",
+                TweenProperty::Scale {
+                    from: Vec3::splat(area.radius * EXPLOSION_START_SCALE),
+                    to: Vec3::splat(area.radius),
+                },
+                EXPLOSION_GROW_DURATION,
+            )
+            .with_easing(Easing::QuadOut),
+        );
+        commands.spawn((
+            PbrBundle {
+                mesh: mesh.0.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(area.center)
+                    .with_scale(Vec3::splat(area.radius * EXPLOSION_START_SCALE)),
+                ..default()
+            },
+            // Already its own, so there is nothing to copy.
+            EffectMaterials {
+                materials: vec![material],
+                emissive: vec![EXPLOSION_COLOR],
+            },
+            burst,
+            Lifetime {
+                timer: Timer::from_seconds(EXPLOSION_LIFETIME, TimerMode::Once),
+            },
+            NotShadowCaster,
+            NotShadowReceiver,
+            Name::new("Explosion"),
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+use bevy::{
+    pbr::{NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+    render::{
+        mesh::Indices,
+        render_resource::{Extent3d, PrimitiveTopology, TextureDimension, TextureFormat},
+        texture::ImageSampler,
+    },
+};
+use bevy_iso3d_rts_cursor_plugin::{Pickable, Selected as CursorSelected};
+
+use crate::{
+    components::{
+        mechanics::{Selected, Team},
+        transport::Loaded,
+        vision::{FogOfWar, FogState, Fogged, TeamVision},
+    },
+    constants::{constants::GROUND_LEVEL, plane::*, vision::*},
+    systems::spawn_unit::decorate_units,
+    GameState,
+};
+
+/// Darkens the parts of the board the player has not explored or cannot see
+/// right now, and hides enemy units standing there.
+pub struct FogOverlayPlugin;
+
+impl Plugin for FogOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_fog_overlay)
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(paint_fog_overlay)
+                    // So units fogged as they spawn have a `Visibility` to hide and
+                    // keep their `Pickable` removed.
+                    .with_system(hide_fogged_units.after(decorate_units)),
+            )
+            // `Fogged` is removed during the update stage, and removals are only
+            // seen by later stages of the same frame.
+            .add_system_to_stage(CoreStage::PostUpdate, reveal_unfogged_units);
+    }
+}
+
+#[derive(Resource)]
+pub struct FogOverlay {
+    pub texture: Handle<Image>,
+}
+
+pub fn fog_alpha(state: FogState) -> u8 {
+    match state {
+        FogState::Unexplored => FOG_UNEXPLORED_ALPHA,
+        FogState::Explored => FOG_EXPLORED_ALPHA,
+        FogState::Visible => 0,
+    }
+}
+
+/// A flat quad over the playing area, with one texel per fog cell. The
+/// texture's rows run along +z so texel `(x, z)` lands on fog cell `(x, z)`.
+fn fog_mesh() -> Mesh {
+    let y = GROUND_LEVEL + FOG_OVERLAY_HEIGHT;
+    let corners = [
+        (GAME_X_MIN, GAME_Z_MIN, [0., 0.]),
+        (GAME_X_MAX, GAME_Z_MIN, [1., 0.]),
+        (GAME_X_MAX, GAME_Z_MAX, [1., 1.]),
+        (GAME_X_MIN, GAME_Z_MAX, [0., 1.]),
+    ];
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        corners.map(|(x, z, _)| [x, y, z]).to_vec(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0., 1., 0.]; 4]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, corners.map(|(.., uv)| uv).to_vec());
+    mesh.set_indices(Some(Indices::U32(vec![0, 2, 1, 0, 3, 2])));
+    mesh
+}
+
+fn setup_fog_overlay(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let vision = TeamVision::default();
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: vision.width as u32,
+            height: vision.depth as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, FOG_UNEXPLORED_ALPHA],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    // Blend between cells rather than showing hard squares.
+    image.sampler_descriptor = ImageSampler::linear();
+    let texture = images.add(image);
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(fog_mesh()),
+            material: materials.add(StandardMaterial {
+                base_color_texture: Some(texture.clone()),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }),
+            ..default()
+        },
+        NotShadowCaster,
+        NotShadowReceiver,
+        Name::new("FogOverlay"),
+    ));
+    commands.insert_resource(FogOverlay { texture });
+}
+
+fn paint_fog_overlay(
+    fog: Res<FogOfWar>,
+    overlay: Res<FogOverlay>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !fog.is_changed() {
+        return;
+    }
+    let Some(vision) = fog.vision(Team::PLAYER) else {
+        return;
+    };
+    let Some(image) = images.get_mut(&overlay.texture) else {
+        return;
+    };
+
+    for z in 0..vision.depth {
+        for x in 0..vision.width {
+            let offset = (z * vision.width + x) * 4;
+            image.data[offset + 3] = fog_alpha(vision.get((x, z)));
+        }
+    }
+}
+
+/// Fogged units are not drawn and cannot be picked or stay selected.
+fn hide_fogged_units(
+    mut commands: Commands,
+    fogged: Query<Entity, Added<Fogged>>,
+    mut visibilities: Query<&mut Visibility>,
+) {
+    for unit in &fogged {
+        if let Ok(mut visibility) = visibilities.get_mut(unit) {
+            visibility.is_visible = false;
+        }
+        commands
+            .entity(unit)
+            .remove::<Pickable>()
+            .remove::<Selected>()
+            .remove::<CursorSelected>();
+    }
+}
+
+/// Draws units that came back into sight and lets them be picked again,
+/// unless they are inside a transport.
+fn reveal_unfogged_units(
+    mut commands: Commands,
+    revealed: RemovedComponents<Fogged>,
+    mut visibilities: Query<&mut Visibility, (Without<Fogged>, Without<Loaded>)>,
+) {
+    for unit in revealed.iter() {
+        let Ok(mut visibility) = visibilities.get_mut(unit) else {
+            continue;
+        };
+        visibility.is_visible = true;
+        commands.entity(unit).insert(Pickable);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+    use bevy_iso3d_rts_cursor_plugin::Pickable;
+
+    use super::{fog_alpha, hide_fogged_units, reveal_unfogged_units};
+    use crate::components::vision::{FogState, Fogged};
+
+    #[derive(Resource)]
+    struct InSight(bool);
+
+    /// Lifts the fog during the update stage, as `mark_fogged_system` does.
+    fn lift_fog(
+        mut commands: Commands,
+        in_sight: Res<InSight>,
+        fogged: Query<Entity, With<Fogged>>,
+    ) {
+        if in_sight.0 {
+            for unit in &fogged {
+                commands.entity(unit).remove::<Fogged>();
+            }
+        }
+    }
+
+    #[test]
+    fn fog_thins_as_cells_are_explored_and_seen() {
+        assert!(fog_alpha(FogState::Unexplored) > fog_alpha(FogState::Explored));
+        assert_eq!(fog_alpha(FogState::Visible), 0);
+    }
+
+    #[test]
+    fn units_are_drawn_and_pickable_again_once_back_in_sight() {
+        let mut app = App::new();
+        app.insert_resource(InSight(false))
+            .add_system(lift_fog)
+            .add_system(hide_fogged_units)
+            .add_system_to_stage(CoreStage::PostUpdate, reveal_unfogged_units);
+        let unit = app
+            .world
+            .spawn((VisibilityBundle::default(), Pickable, Fogged))
+            .id();
+
+        app.update();
+        assert!(!app.world.get::<Visibility>(unit).unwrap().is_visible);
+        assert!(app.world.get::<Pickable>(unit).is_none());
+
+        app.world.resource_mut::<InSight>().0 = true;
+        app.update();
+        assert!(app.world.get::<Visibility>(unit).unwrap().is_visible);
+        assert!(app.world.get::<Pickable>(unit).is_some());
+    }
+}
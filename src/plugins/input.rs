@@ -0,0 +1,27 @@
+use bevy::{input::InputSystem, prelude::*};
+
+use crate::{
+    components::input::{ActionState, InputBindings, PendingRebind, RebindRequest},
+    constants::input::{BINDINGS_FILE, USER_BINDINGS_FILE},
+    systems::input::{rebind_system, update_action_state},
+};
+
+/// Maps keyboard, mouse and gamepad input to `Action`s through the bindings file
+/// and the player's own rebinds.
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        let bindings =
+            InputBindings::load_or_default(BINDINGS_FILE).with_overrides(USER_BINDINGS_FILE);
+        app.insert_resource(bindings)
+            .init_resource::<ActionState>()
+            .init_resource::<PendingRebind>()
+            .add_event::<RebindRequest>()
+            .add_system_to_stage(CoreStage::PreUpdate, rebind_system.after(InputSystem))
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_action_state.after(rebind_system),
+            );
+    }
+}
@@ -0,0 +1,357 @@
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use crate::{
+    components::{
+        input::{Action, ActionState},
+        mechanics::{Selected, Team, Unit},
+        vision::Fogged,
+    },
+    constants::{constants::GROUND_LEVEL, minimap::*, plane::*},
+    plugins::{
+        camera::{CameraFocusEvent, RtsCamera},
+        command_card::CommandTargeting,
+    },
+    systems::commands::{CommandEvent, UnitCommand},
+    Game, GameState,
+};
+
+/// A top-down overview of the board in the bottom-left corner. Left-click moves
+/// the camera, right-click orders the selection to move there.
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_minimap).add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                .with_system(paint_minimap_board)
+                .with_system(spawn_minimap_blips)
+                .with_system(update_minimap_blips)
+                .with_system(update_minimap_frustum)
+                .with_system(minimap_click),
+        );
+    }
+}
+
+#[derive(Resource)]
+pub struct Minimap {
+    pub texture: Handle<Image>,
+    painted_cells: usize,
+}
+
+#[derive(Component)]
+pub struct MinimapPanel;
+
+#[derive(Component)]
+pub struct MinimapBlip {
+    pub unit: Entity,
+}
+
+#[derive(Component)]
+pub struct MinimapFrustumEdge;
+
+/// Where `position` falls on the minimap, from (0, 0) at the top left to
+/// (1, 1) at the bottom right. The top of the minimap is the far (-z) edge.
+pub fn world_to_minimap(position: Vec3) -> Vec2 {
+    Vec2::new(
+        (position.x - GAME_X_MIN) / (GAME_X_MAX - GAME_X_MIN),
+        (position.z - GAME_Z_MIN) / (GAME_Z_MAX - GAME_Z_MIN),
+    )
+    .clamp(Vec2::ZERO, Vec2::ONE)
+}
+
+pub fn minimap_to_world(point: Vec2) -> Vec3 {
+    Vec3::new(
+        GAME_X_MIN + point.x * (GAME_X_MAX - GAME_X_MIN),
+        GROUND_LEVEL,
+        GAME_Z_MIN + point.y * (GAME_Z_MAX - GAME_Z_MIN),
+    )
+}
+
+/// The cursor's position on the minimap, if it is over it. Window cursor
+/// positions have their origin at the bottom left.
+pub fn cursor_on_minimap(cursor: Vec2) -> Option<Vec2> {
+    let point = Vec2::new(
+        (cursor.x - MINIMAP_MARGIN) / MINIMAP_SIZE,
+        1. - (cursor.y - MINIMAP_MARGIN) / MINIMAP_SIZE,
+    );
+    let inside = point.cmpge(Vec2::ZERO).all() && point.cmple(Vec2::ONE).all();
+    inside.then_some(point)
+}
+
+fn setup_minimap(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let texture = images.add(Image::new_fill(
+        Extent3d {
+            width: MINIMAP_TEXTURE_SIZE,
+            height: MINIMAP_TEXTURE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+    ));
+
+    commands
+        .spawn((
+            ImageBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(MINIMAP_MARGIN),
+                        bottom: Val::Px(MINIMAP_MARGIN),
+                        ..default()
+                    },
+                    size: Size::new(Val::Px(MINIMAP_SIZE), Val::Px(MINIMAP_SIZE)),
+                    ..default()
+                },
+                image: UiImage(texture.clone()),
+                ..default()
+            },
+            MinimapPanel,
+            Name::new("Minimap"),
+        ))
+        .with_children(|panel| {
+            for _ in 0..4 {
+                panel.spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            ..default()
+                        },
+                        background_color: MINIMAP_FRUSTUM_COLOR.into(),
+                        ..default()
+                    },
+                    MinimapFrustumEdge,
+                ));
+            }
+        });
+
+    commands.insert_resource(Minimap {
+        texture,
+        painted_cells: 0,
+    });
+}
+
+// The board is built on entering `GameState::Playing`, so paint it whenever
+// the number of cells changes rather than at startup.
+fn paint_minimap_board(
+    game: Res<Game>,
+    mut minimap: ResMut<Minimap>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let cells = game.board.iter().map(|row| row.len()).sum::<usize>();
+    if cells == 0 || cells == minimap.painted_cells {
+        return;
+    }
+    let Some(image) = images.get_mut(&minimap.texture) else {
+        return;
+    };
+
+    let size = MINIMAP_TEXTURE_SIZE as usize;
+    for y in 0..size {
+        for x in 0..size {
+            let world = minimap_to_world(Vec2::new(
+                (x as f32 + 0.5) / size as f32,
+                (y as f32 + 0.5) / size as f32,
+            ));
+            let offset = (y * size + x) * 4;
+            // The playing area reaches a little past the board; leave that dark.
+            let Some((i, j)) = game.cell_index(world) else {
+                image.data[offset..offset + 4].copy_from_slice(&Color::BLACK.as_rgba_u8());
+                continue;
+            };
+            let height = game.cell((i, j)).map_or(0., |cell| cell.height);
+
+            // Checker the cells so the board layout reads at a glance.
+            let shade = if (i + j) % 2 == 0 { 0.35 } else { 0.3 } + height * 0.05;
+            let color = Color::rgb(shade * 0.6, shade, shade * 0.5).as_rgba_u8();
+            image.data[offset..offset + 4].copy_from_slice(&color);
+        }
+    }
+
+    minimap.painted_cells = cells;
+}
+
+fn spawn_minimap_blips(
+    mut commands: Commands,
+    panels: Query<Entity, With<MinimapPanel>>,
+    units: Query<(Entity, Option<&Team>), Added<Unit>>,
+) {
+    let Ok(panel) = panels.get_single() else {
+        return;
+    };
+
+    for (unit, team) in &units {
+        let blip = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        size: Size::new(Val::Px(MINIMAP_BLIP_SIZE), Val::Px(MINIMAP_BLIP_SIZE)),
+                        ..default()
+                    },
+                    background_color: team.copied().unwrap_or_default().color().into(),
+                    ..default()
+                },
+                MinimapBlip { unit },
+            ))
+            .id();
+        commands.entity(panel).add_child(blip);
+    }
+}
+
+fn update_minimap_blips(
+    mut commands: Commands,
+    mut blips: Query<(Entity, &MinimapBlip, &mut Style)>,
+    units: Query<(&Transform, Option<&Fogged>), With<Unit>>,
+) {
+    for (blip, MinimapBlip { unit }, mut style) in &mut blips {
+        let Ok((transform, fogged)) = units.get(*unit) else {
+            commands.entity(blip).despawn_recursive();
+            continue;
+        };
+        style.display = if fogged.is_some() {
+            Display::None
+        } else {
+            Display::Flex
+        };
+
+        let point = world_to_minimap(transform.translation) * MINIMAP_SIZE;
+        style.position.left = Val::Px(point.x - MINIMAP_BLIP_SIZE / 2.);
+        style.position.top = Val::Px(point.y - MINIMAP_BLIP_SIZE / 2.);
+    }
+}
+
+/// Outlines the part of the ground the main camera can see.
+fn update_minimap_frustum(
+    cameras: Query<(&Camera, &GlobalTransform), With<RtsCamera>>,
+    mut edges: Query<&mut Style, With<MinimapFrustumEdge>>,
+) {
+    let Ok((camera, camera_transform)) = cameras.get_single() else {
+        return;
+    };
+    let Some(viewport) = camera.logical_viewport_size() else {
+        return;
+    };
+
+    let corners = [
+        Vec2::ZERO,
+        Vec2::new(viewport.x, 0.),
+        Vec2::new(0., viewport.y),
+        viewport,
+    ];
+    let mut min = Vec2::ONE;
+    let mut max = Vec2::ZERO;
+    for corner in corners {
+        let Some(ray) = camera.viewport_to_world(camera_transform, corner) else {
+            continue;
+        };
+        // Corners above the horizon never reach the ground; stop at the map edge.
+        let distance = if ray.direction.y < 0. {
+            (GROUND_LEVEL - ray.origin.y) / ray.direction.y
+        } else {
+            GAME_X_MAX.max(GAME_Z_MAX) * 2.
+        };
+        let point = world_to_minimap(ray.origin + ray.direction * distance);
+        min = min.min(point);
+        max = max.max(point);
+    }
+
+    let min = min * MINIMAP_SIZE;
+    let max = max * MINIMAP_SIZE;
+    let size = (max - min).max(Vec2::splat(MINIMAP_FRUSTUM_THICKNESS));
+    let thickness = MINIMAP_FRUSTUM_THICKNESS;
+    // top, bottom, left, right
+    let rects = [
+        (min, Vec2::new(size.x, thickness)),
+        (
+            Vec2::new(min.x, max.y - thickness),
+            Vec2::new(size.x, thickness),
+        ),
+        (min, Vec2::new(thickness, size.y)),
+        (
+            Vec2::new(max.x - thickness, min.y),
+            Vec2::new(thickness, size.y),
+        ),
+    ];
+    for (mut style, (position, size)) in edges.iter_mut().zip(rects) {
+        style.position.left = Val::Px(position.x);
+        style.position.top = Val::Px(position.y);
+        style.size = Size::new(Val::Px(size.x), Val::Px(size.y));
+    }
+}
+
+fn minimap_click(
+    windows: Res<Windows>,
+    actions: Res<ActionState>,
+    targeting: Option<Res<CommandTargeting>>,
+    selected: Query<Entity, (With<Selected>, With<Unit>)>,
+    mut focus_events: EventWriter<CameraFocusEvent>,
+    mut command_events: EventWriter<CommandEvent>,
+) {
+    let Some(point) = windows
+        .get_primary()
+        .and_then(|window| window.cursor_position())
+        .and_then(cursor_on_minimap)
+    else {
+        return;
+    };
+    // Clicks that give or cancel a command card order are not camera moves.
+    if targeting.map_or(false, |targeting| {
+        targeting.0.is_some() || targeting.is_changed()
+    }) {
+        return;
+    }
+    let destination = minimap_to_world(point);
+
+    if actions.just_pressed(Action::Select) {
+        focus_events.send(CameraFocusEvent(destination));
+    }
+    if actions.just_pressed(Action::Command) {
+        command_events.send(CommandEvent {
+            units: selected.iter().collect(),
+            command: UnitCommand::Move(destination),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{cursor_on_minimap, minimap_to_world, world_to_minimap};
+    use crate::constants::{
+        constants::GROUND_LEVEL,
+        minimap::{MINIMAP_MARGIN, MINIMAP_SIZE},
+    };
+
+    #[test]
+    fn world_and_minimap_positions_round_trip() {
+        let position = Vec3::new(6., GROUND_LEVEL, 18.);
+
+        let round_trip = minimap_to_world(world_to_minimap(position));
+
+        assert!(round_trip.abs_diff_eq(position, 0.001));
+    }
+
+    #[test]
+    fn positions_off_the_map_stick_to_its_edge() {
+        let point = world_to_minimap(Vec3::new(-50., 0., 500.));
+
+        assert_eq!(point, Vec2::new(0., 1.));
+    }
+
+    #[test]
+    fn cursor_at_bottom_left_of_minimap_is_its_bottom_left() {
+        let point = cursor_on_minimap(Vec2::splat(MINIMAP_MARGIN)).unwrap();
+
+        assert_eq!(point, Vec2::new(0., 1.));
+    }
+
+    #[test]
+    fn cursor_outside_minimap_is_ignored() {
+        assert!(cursor_on_minimap(Vec2::splat(MINIMAP_MARGIN + MINIMAP_SIZE + 1.)).is_none());
+    }
+}
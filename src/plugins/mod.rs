@@ -1,2 +1,44 @@
 pub mod animation;
+pub mod buildings;
+pub mod camera;
+pub mod combat;
+pub mod command_card;
+pub mod economy;
+pub mod effects;
+pub mod explosions;
+pub mod fog;
+pub mod input;
+pub mod minimap;
+pub mod movement;
+pub mod overlays;
+pub mod placement;
+pub mod selection;
+pub mod selection_panel;
+pub mod simulation;
+pub mod spawner;
+pub mod transport;
+pub mod ui;
+pub mod vision;
+pub mod world;
 pub use animation::*;
+pub use buildings::*;
+pub use camera::*;
+pub use combat::*;
+pub use command_card::*;
+pub use economy::*;
+pub use effects::*;
+pub use explosions::*;
+pub use fog::*;
+pub use input::*;
+pub use minimap::*;
+pub use movement::*;
+pub use overlays::*;
+pub use placement::*;
+pub use selection::*;
+pub use selection_panel::*;
+pub use simulation::*;
+pub use spawner::*;
+pub use transport::*;
+pub use ui::*;
+pub use vision::*;
+pub use world::*;
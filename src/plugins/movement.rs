@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+
+use crate::{
+    systems::{
+        movement::{adjust_still_units_system, movement_system, steer_physics_units_system},
+        rotation::rotate_system,
+        stuck::{stuck_detection_system, OrderFailed},
+    },
+    GameState,
+};
+
+pub struct MovementPlugin;
+
+impl Plugin for MovementPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OrderFailed>().add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                .with_system(rotate_system)
+                .with_system(movement_system)
+                .with_system(adjust_still_units_system)
+                .with_system(steer_physics_units_system)
+                .with_system(stuck_detection_system),
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use bevy::{
+    pbr::{NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+    render::{mesh::Indices, render_resource::PrimitiveTopology, view::NoFrustumCulling},
+};
+
+use crate::{
+    components::{
+        mechanics::{Health, Selected, SelectionHighlighter, Unit},
+        vision::Fogged,
+    },
+    constants::{hud::HEALTH_BAR_BACKGROUND_COLOR, overlays::*},
+    plugins::{camera::RtsCamera, selection_panel::health_color},
+    GameState,
+};
+
+/// Selection rings under `Selected` units and billboarded health bars over
+/// damaged ones. Each kind is one mesh rebuilt every frame, so hundreds of
+/// units cost a single draw call per kind.
+pub struct OverlayPlugin;
+
+impl Plugin for OverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_overlays).add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                .with_system(update_selection_rings)
+                .with_system(update_world_health_bars),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct HealthBarOverlay;
+
+/// Vertex data for one batched overlay mesh.
+#[derive(Default)]
+pub struct OverlayGeometry {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub colors: Vec<[f32; 4]>,
+    pub indices: Vec<u32>,
+}
+
+impl OverlayGeometry {
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// A flat annulus on the ground around `center`.
+    pub fn push_ring(
+        &mut self,
+        center: Vec3,
+        inner: f32,
+        outer: f32,
+        segments: usize,
+        color: Color,
+    ) {
+        let start = self.positions.len() as u32;
+        for segment in 0..segments {
+            let angle = segment as f32 / segments as f32 * std::f32::consts::TAU;
+            let direction = Vec3::new(angle.cos(), 0., angle.sin());
+            for radius in [inner, outer] {
+                self.positions.push((center + direction * radius).into());
+                self.normals.push(Vec3::Y.into());
+                self.colors.push(color.as_linear_rgba_f32());
+            }
+        }
+        for segment in 0..segments as u32 {
+            let next = (segment + 1) % segments as u32;
+            let (a_inner, a_outer) = (start + segment * 2, start + segment * 2 + 1);
+            let (b_inner, b_outer) = (start + next * 2, start + next * 2 + 1);
+            self.indices
+                .extend([a_inner, b_inner, a_outer, a_outer, b_inner, b_outer]);
+        }
+    }
+
+    /// A quad facing the camera, spanned by the camera's `right` and `up`,
+    /// growing rightward from the middle of its left edge.
+    pub fn push_billboard(
+        &mut self,
+        left_center: Vec3,
+        right: Vec3,
+        up: Vec3,
+        size: Vec2,
+        color: Color,
+    ) {
+        let start = self.positions.len() as u32;
+        let half_up = up * size.y / 2.;
+        let across = right * size.x;
+        let normal = right.cross(up);
+        for corner in [
+            left_center - half_up,
+            left_center + across - half_up,
+            left_center + across + half_up,
+            left_center + half_up,
+        ] {
+            self.positions.push(corner.into());
+            self.normals.push(normal.into());
+            self.colors.push(color.as_linear_rgba_f32());
+        }
+        self.indices
+            .extend([start, start + 1, start + 2, start, start + 2, start + 3]);
+    }
+
+    pub fn write_to(self, mesh: &mut Mesh) {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, self.colors);
+        mesh.set_indices(Some(Indices::U32(self.indices)));
+    }
+}
+
+// GPU buffers cannot be empty, so start from a single degenerate triangle.
+fn placeholder_mesh() -> Mesh {
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    let mut geometry = OverlayGeometry::default();
+    geometry.push_billboard(Vec3::ZERO, Vec3::ZERO, Vec3::ZERO, Vec2::ZERO, Color::NONE);
+    geometry.indices.truncate(3);
+    geometry.write_to(&mut mesh);
+    mesh
+}
+
+fn setup_overlays(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        unlit: true,
+        cull_mode: None,
+        ..default()
+    });
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(placeholder_mesh()),
+            material: material.clone(),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        },
+        NoFrustumCulling,
+        NotShadowCaster,
+        NotShadowReceiver,
+        SelectionHighlighter,
+        Name::new("SelectionRings"),
+    ));
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(placeholder_mesh()),
+            material,
+            visibility: Visibility { is_visible: false },
+            ..default()
+        },
+        NoFrustumCulling,
+        NotShadowCaster,
+        NotShadowReceiver,
+        HealthBarOverlay,
+        Name::new("HealthBars"),
+    ));
+}
+
+fn write_overlay(
+    geometry: OverlayGeometry,
+    mesh: &Handle<Mesh>,
+    visibility: &mut Visibility,
+    meshes: &mut Assets<Mesh>,
+) {
+    visibility.is_visible = !geometry.is_empty();
+    if geometry.is_empty() {
+        return;
+    }
+    if let Some(mesh) = meshes.get_mut(mesh) {
+        geometry.write_to(mesh);
+    }
+}
+
+fn update_selection_rings(
+    mut overlays: Query<(&Handle<Mesh>, &mut Visibility), With<SelectionHighlighter>>,
+    units: Query<&GlobalTransform, (With<Selected>, With<Unit>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let Ok((mesh, mut visibility)) = overlays.get_single_mut() else {
+        return;
+    };
+
+    let mut geometry = OverlayGeometry::default();
+    for transform in &units {
+        geometry.push_ring(
+            transform.translation() + Vec3::Y * SELECTION_RING_HEIGHT,
+            SELECTION_RING_INNER_RADIUS,
+            SELECTION_RING_OUTER_RADIUS,
+            SELECTION_RING_SEGMENTS,
+            SELECTION_RING_COLOR,
+        );
+    }
+
+    write_overlay(geometry, mesh, &mut visibility, &mut meshes);
+}
+
+fn update_world_health_bars(
+    mut overlays: Query<(&Handle<Mesh>, &mut Visibility), With<HealthBarOverlay>>,
+    cameras: Query<&GlobalTransform, With<RtsCamera>>,
+    // Bars over fogged enemies would give away where they are.
+    units: Query<(&GlobalTransform, &Health), (With<Unit>, Without<Fogged>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let Ok((mesh, mut visibility)) = overlays.get_single_mut() else {
+        return;
+    };
+    let Ok(camera) = cameras.get_single() else {
+        return;
+    };
+    let (right, up, back) = (camera.right(), camera.up(), camera.back());
+
+    let mut geometry = OverlayGeometry::default();
+    for (transform, health) in &units {
+        let fraction = health.fraction();
+        if fraction >= 1. {
+            continue;
+        }
+
+        let left_center = transform.translation() + Vec3::Y * WORLD_HEALTH_BAR_OFFSET
+            - right * WORLD_HEALTH_BAR_WIDTH / 2.;
+        let size = Vec2::new(WORLD_HEALTH_BAR_WIDTH, WORLD_HEALTH_BAR_HEIGHT);
+        geometry.push_billboard(left_center, right, up, size, HEALTH_BAR_BACKGROUND_COLOR);
+        // Nudge the fill toward the camera so it always draws over the background.
+        geometry.push_billboard(
+            left_center + back * 0.01,
+            right,
+            up,
+            Vec2::new(size.x * fraction, size.y),
+            health_color(fraction),
+        );
+    }
+
+    write_overlay(geometry, mesh, &mut visibility, &mut meshes);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::OverlayGeometry;
+
+    #[test]
+    fn ring_has_two_vertices_and_two_triangles_per_segment() {
+        let mut geometry = OverlayGeometry::default();
+
+        geometry.push_ring(Vec3::ZERO, 0.5, 1., 16, Color::GREEN);
+
+        assert_eq!(geometry.positions.len(), 32);
+        assert_eq!(geometry.indices.len(), 16 * 6);
+        assert!(geometry.indices.iter().all(|index| *index < 32));
+    }
+
+    #[test]
+    fn ring_vertices_lie_between_its_radii() {
+        let mut geometry = OverlayGeometry::default();
+        let center = Vec3::new(3., 7.75, 4.);
+
+        geometry.push_ring(center, 0.5, 1., 8, Color::GREEN);
+
+        for position in geometry.positions {
+            let distance = Vec3::from(position).distance(center);
+            assert!((0.499..=1.001).contains(&distance));
+        }
+    }
+
+    #[test]
+    fn later_shapes_index_their_own_vertices() {
+        let mut geometry = OverlayGeometry::default();
+
+        geometry.push_billboard(Vec3::ZERO, Vec3::X, Vec3::Y, Vec2::ONE, Color::RED);
+        geometry.push_billboard(Vec3::Z, Vec3::X, Vec3::Y, Vec2::ONE, Color::RED);
+
+        assert_eq!(&geometry.indices[6..], &[4, 5, 6, 4, 6, 7]);
+    }
+
+    #[test]
+    fn billboard_spans_right_and_up_from_its_left_edge() {
+        let mut geometry = OverlayGeometry::default();
+
+        geometry.push_billboard(Vec3::ZERO, Vec3::X, Vec3::Y, Vec2::new(2., 1.), Color::RED);
+
+        assert_eq!(geometry.positions[0], [0., -0.5, 0.]);
+        assert_eq!(geometry.positions[2], [2., 0.5, 0.]);
+    }
+}
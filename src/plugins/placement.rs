@@ -0,0 +1,157 @@
+use bevy::{pbr::NotShadowCaster, prelude::*};
+use bevy_iso3d_rts_cursor_plugin::{Cursor, Pickable};
+
+use crate::{
+    components::{
+        buildings::{Building, BuildingDefinitions, UnderConstruction},
+        economy::Stockpiles,
+        mechanics::Team,
+    },
+    constants::{economy::*, plane::CELL_SIZE},
+    plugins::command_card::{CommandTargeting, Targeting},
+    Game, GameState,
+};
+
+/// The ghost that previews where a building will go, and the meshes of placed
+/// buildings. `BuildingsPlugin` does the placing itself.
+pub struct PlacementPlugin;
+
+impl Plugin for PlacementPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                .with_system(update_placement_ghost)
+                .with_system(decorate_buildings)
+                .with_system(show_construction_progress.after(decorate_buildings)),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct PlacementGhost;
+
+/// A box standing on the ground, one unit wide and deep, so it can be scaled
+/// to any footprint and grown upward while under construction.
+fn building_mesh() -> Mesh {
+    Mesh::from(shape::Box {
+        min_x: -0.5,
+        max_x: 0.5,
+        min_y: 0.,
+        max_y: BUILDING_HEIGHT,
+        min_z: -0.5,
+        max_z: 0.5,
+    })
+}
+
+fn footprint_scale((width, depth): (usize, usize)) -> Vec3 {
+    Vec3::new(
+        width as f32 * CELL_SIZE * BUILDING_INSET,
+        1.,
+        depth as f32 * CELL_SIZE * BUILDING_INSET,
+    )
+}
+
+/// While a building waits for a spot, a translucent box snaps to the cell
+/// under the cursor, green where it can go and the player can pay for it,
+/// red otherwise.
+fn update_placement_ghost(
+    mut commands: Commands,
+    targeting: Res<CommandTargeting>,
+    cursor: Res<Cursor>,
+    game: Res<Game>,
+    definitions: Res<BuildingDefinitions>,
+    stockpiles: Res<Stockpiles>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut ghosts: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Visibility,
+            &Handle<StandardMaterial>,
+        ),
+        With<PlacementGhost>,
+    >,
+) {
+    let definition = match &targeting.0 {
+        Some(Targeting::Build(kind)) => definitions.get(kind),
+        _ => None,
+    };
+    let Some(definition) = definition else {
+        for (ghost, ..) in &ghosts {
+            commands.entity(ghost).despawn_recursive();
+        }
+        return;
+    };
+
+    let Ok((_, mut transform, mut visibility, material)) = ghosts.get_single_mut() else {
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(building_mesh()),
+                material: materials.add(StandardMaterial {
+                    base_color: PLACEMENT_VALID_COLOR,
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    ..default()
+                }),
+                visibility: Visibility { is_visible: false },
+                ..default()
+            },
+            NotShadowCaster,
+            PlacementGhost,
+            Name::new("PlacementGhost"),
+        ));
+        return;
+    };
+
+    let Some(origin) = game.cell_index(cursor.location.xyz) else {
+        visibility.is_visible = false;
+        return;
+    };
+    visibility.is_visible = true;
+    transform.translation = Game::footprint_center(origin, definition.footprint);
+    transform.scale = footprint_scale(definition.footprint);
+
+    let valid = game.check_placement(origin, definition.footprint).is_ok()
+        && stockpiles.get(Team::PLAYER) >= definition.cost;
+    if let Some(material) = materials.get_mut(material) {
+        material.base_color = if valid {
+            PLACEMENT_VALID_COLOR
+        } else {
+            PLACEMENT_INVALID_COLOR
+        };
+    }
+}
+
+/// Gives placed buildings a box in their team's colour, sized to their
+/// footprint.
+fn decorate_buildings(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    definitions: Res<BuildingDefinitions>,
+    mut buildings: Query<(Entity, &Building, Option<&Team>, &mut Transform), Added<Building>>,
+) {
+    for (entity, building, team, mut transform) in &mut buildings {
+        let footprint = definitions
+            .get(&building.kind)
+            .map_or((1, 1), |definition| definition.footprint);
+        commands.entity(entity).insert((
+            meshes.add(building_mesh()),
+            materials.add(team.copied().unwrap_or_default().color().into()),
+            VisibilityBundle::default(),
+            Pickable,
+        ));
+        transform.scale = footprint_scale(footprint);
+    }
+}
+
+/// Buildings rise out of the ground as their construction timer runs.
+fn show_construction_progress(
+    mut buildings: Query<(&mut Transform, Option<&UnderConstruction>), With<Building>>,
+) {
+    for (mut transform, construction) in &mut buildings {
+        transform.scale.y =
+            construction.map_or(1., |construction| construction.timer.percent().max(0.05));
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+use bevy_iso3d_rts_cursor_plugin::{Aesthetics, CursorPlugin};
+
+use crate::{
+    constants::{constants::GROUND_LEVEL, plane::GAME_BOUNDS},
+    systems::selection::{
+        control_group_system, double_click_select_system, hide_loaded_units,
+        mirror_cursor_selection, selection_changed_system, show_unloaded_units, ControlGroups,
+        SelectionChanged,
+    },
+    GameState,
+};
+
+/// Cursor, raycasting and box selection, configured for the sandbox board, plus
+/// control groups and double-click selection on top of it. Units loaded into
+/// transports are hidden and dropped from the selection.
+pub struct SelectionPlugin;
+
+impl Plugin for SelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(CursorPlugin {
+            bounds: GAME_BOUNDS,
+            aesthetics: Aesthetics {
+                ground_height: GROUND_LEVEL,
+                ..Default::default()
+            },
+        })
+        .init_resource::<ControlGroups>()
+        .add_event::<SelectionChanged>()
+        .add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                .with_system(mirror_cursor_selection)
+                .with_system(control_group_system)
+                .with_system(double_click_select_system)
+                .with_system(hide_loaded_units),
+        )
+        // Runs after commands from the update stage are applied, so every change
+        // made this frame is seen.
+        .add_system_to_stage(CoreStage::PostUpdate, selection_changed_system)
+        .add_system_to_stage(CoreStage::PostUpdate, show_unloaded_units);
+    }
+}
@@ -0,0 +1,364 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        buildings::{ProductionQueue, RallyPoint, UnderConstruction},
+        input::{Action, ActionState},
+        mechanics::{Destination, Health, MovementSpeed, Selected, Team, Unit, UnitType},
+        orders::Order,
+        research::Researching,
+        stats::{effective, Stat, StatEffects},
+        transport::Transport,
+    },
+    constants::{hud::*, minimap::*},
+    systems::selection::{select_only, SelectionChanged},
+    GameState,
+};
+
+/// The bottom HUD panel listing the selection. Many units are shown as icons
+/// grouped by type; a single unit gets a details readout.
+pub struct SelectionPanelPlugin;
+
+impl Plugin for SelectionPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_selection_panel)
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(rebuild_selection_panel)
+                    .with_system(update_selection_health_bars.after(rebuild_selection_panel))
+                    .with_system(update_selection_details.after(rebuild_selection_panel))
+                    .with_system(selection_icon_click),
+            );
+    }
+}
+
+#[derive(Component)]
+pub struct SelectionPanel;
+
+#[derive(Component)]
+pub struct SelectionIcon {
+    pub unit: Entity,
+}
+
+#[derive(Component)]
+pub struct SelectionHealthBar {
+    pub unit: Entity,
+}
+
+#[derive(Component)]
+pub struct SelectionDetails {
+    pub unit: Entity,
+}
+
+pub fn health_color(fraction: f32) -> Color {
+    let [r1, g1, b1, _] = HEALTH_BAR_DAMAGED_COLOR.as_rgba_f32();
+    let [r2, g2, b2, _] = HEALTH_BAR_HEALTHY_COLOR.as_rgba_f32();
+    let t = fraction.clamp(0., 1.);
+    let mix = |damaged: f32, healthy: f32| damaged * (1. - t) + healthy * t;
+    Color::rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+}
+
+fn text_style(asset_server: &AssetServer) -> TextStyle {
+    TextStyle {
+        font: asset_server.load(HUD_FONT),
+        font_size: HUD_FONT_SIZE,
+        color: HUD_TEXT_COLOR,
+    }
+}
+
+fn setup_selection_panel(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(MINIMAP_MARGIN * 2. + MINIMAP_SIZE),
+                    right: Val::Px(HUD_MARGIN * 2. + COMMAND_CARD_WIDTH),
+                    bottom: Val::Px(HUD_MARGIN),
+                    ..default()
+                },
+                size: Size::new(Val::Auto, Val::Px(SELECTION_PANEL_HEIGHT)),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(4.)),
+                ..default()
+            },
+            background_color: HUD_BACKGROUND_COLOR.into(),
+            ..default()
+        },
+        // Keeps clicks on the panel from reaching the map behind it.
+        Interaction::default(),
+        SelectionPanel,
+        Name::new("SelectionPanel"),
+    ));
+}
+
+fn spawn_health_bar(parent: &mut ChildBuilder, unit: Entity, width: Val) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(width, Val::Px(HEALTH_BAR_HEIGHT)),
+                ..default()
+            },
+            background_color: HEALTH_BAR_BACKGROUND_COLOR.into(),
+            ..default()
+        })
+        .with_children(|bar| {
+            bar.spawn((
+                NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                        ..default()
+                    },
+                    background_color: HEALTH_BAR_HEALTHY_COLOR.into(),
+                    ..default()
+                },
+                SelectionHealthBar { unit },
+            ));
+        });
+}
+
+fn rebuild_selection_panel(
+    mut commands: Commands,
+    mut selection_changed: EventReader<SelectionChanged>,
+    asset_server: Res<AssetServer>,
+    panels: Query<Entity, With<SelectionPanel>>,
+    units: Query<(Entity, Option<&UnitType>, Option<&Team>), With<Unit>>,
+) {
+    let Some(SelectionChanged { selected }) = selection_changed.iter().last() else {
+        return;
+    };
+    let Ok(panel) = panels.get_single() else {
+        return;
+    };
+    commands.entity(panel).despawn_descendants();
+
+    let style = text_style(&asset_server);
+    let units: Vec<_> = units.iter_many(selected).collect();
+
+    if let [(unit, ..)] = units.as_slice() {
+        let unit = *unit;
+        commands.entity(panel).with_children(|panel| {
+            panel.spawn((
+                TextBundle::from_section("", style.clone()),
+                SelectionDetails { unit },
+            ));
+            spawn_health_bar(panel, unit, Val::Px(120.));
+        });
+        return;
+    }
+
+    let mut by_type: BTreeMap<String, Vec<(Entity, Team)>> = BTreeMap::new();
+    for (unit, unit_type, team) in units {
+        let unit_type = unit_type.map_or_else(|| "unit".to_string(), |t| t.0.clone());
+        by_type
+            .entry(unit_type)
+            .or_default()
+            .push((unit, team.copied().unwrap_or_default()));
+    }
+
+    let mut icons_left = SELECTION_MAX_ICONS;
+    commands.entity(panel).with_children(|panel| {
+        for (unit_type, members) in by_type {
+            panel
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn(TextBundle::from_section(
+                        format!("{} x{} ", unit_type, members.len()),
+                        style.clone(),
+                    ));
+
+                    for (unit, team) in members.into_iter().take(icons_left) {
+                        icons_left -= 1;
+                        row.spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    size: Size::new(
+                                        Val::Px(SELECTION_ICON_WIDTH),
+                                        Val::Px(SELECTION_ICON_HEIGHT),
+                                    ),
+                                    margin: UiRect::all(Val::Px(1.)),
+                                    flex_direction: FlexDirection::ColumnReverse,
+                                    ..default()
+                                },
+                                background_color: team.color().into(),
+                                ..default()
+                            },
+                            SelectionIcon { unit },
+                        ))
+                        .with_children(|icon| spawn_health_bar(icon, unit, Val::Percent(100.)));
+                    }
+                });
+        }
+    });
+}
+
+fn update_selection_health_bars(
+    mut bars: Query<(&SelectionHealthBar, &mut Style, &mut BackgroundColor)>,
+    units: Query<&Health>,
+) {
+    for (bar, mut style, mut color) in &mut bars {
+        let fraction = units.get(bar.unit).map_or(0., |health| health.fraction());
+        style.size.width = Val::Percent(fraction * 100.);
+        *color = health_color(fraction).into();
+    }
+}
+
+fn update_selection_details(
+    mut details: Query<(&SelectionDetails, &mut Text)>,
+    units: Query<(
+        Option<&UnitType>,
+        Option<&Health>,
+        Option<&MovementSpeed>,
+        Option<&Order>,
+        Option<&Destination>,
+        Option<&UnderConstruction>,
+        Option<&ProductionQueue>,
+        Option<&RallyPoint>,
+        Option<&Transport>,
+        Option<&Researching>,
+        Option<&StatEffects>,
+    )>,
+    cargo_types: Query<&UnitType>,
+) {
+    for (SelectionDetails { unit }, mut text) in &mut details {
+        let Ok((
+            unit_type,
+            health,
+            speed,
+            order,
+            destination,
+            construction,
+            queue,
+            rally,
+            transport,
+            researching,
+            effects,
+        )) = units.get(*unit)
+        else {
+            continue;
+        };
+
+        let mut lines = vec![unit_type.map_or_else(|| "unit".to_string(), |t| t.0.clone())];
+        if let Some(health) = health {
+            lines.push(format!("Health: {:.0} / {:.0}", health.value, health.max));
+        }
+        if let Some(construction) = construction {
+            lines.push(format!(
+                "Construction: {:.0}%",
+                construction.timer.percent() * 100.
+            ));
+        }
+        if let Some(queue) = queue {
+            lines.push(match queue.items.front() {
+                Some(front) => format!(
+                    "Producing: {} {:.0}% (+{} queued)",
+                    front.unit,
+                    front.timer.percent() * 100.,
+                    queue.items.len() - 1
+                ),
+                None => "Producing: nothing".to_string(),
+            });
+        }
+        if let Some(researching) = researching {
+            lines.push(format!(
+                "Researching: {} {:.0}%",
+                researching.research,
+                researching.timer.percent() * 100.
+            ));
+        }
+        if let Some(RallyPoint(point)) = rally {
+            lines.push(format!("Rally: ({:.1}, {:.1})", point.x, point.z));
+        }
+        if let Some(transport) = transport {
+            let mut line = format!("Cargo: {} / {}", transport.cargo.len(), transport.capacity);
+            if !transport.cargo.is_empty() {
+                let names: Vec<&str> = cargo_types
+                    .iter_many(&transport.cargo)
+                    .map(|t| t.0.as_str())
+                    .collect();
+                line.push_str(&format!(" ({})", names.join(", ")));
+            }
+            lines.push(line);
+        }
+        if let Some(effects) = effects.filter(|effects| !effects.effects.is_empty()) {
+            let names: Vec<&str> = effects
+                .effects
+                .iter()
+                .map(|effect| effect.source.as_str())
+                .collect();
+            lines.push(format!("Effects: {}", names.join(", ")));
+        }
+        if let Some(speed) = speed {
+            lines.push(format!(
+                "Speed: {:.1}",
+                effective(effects, Stat::Speed, speed.value)
+            ));
+        }
+        lines.push(format!(
+            "Order: {}",
+            order.map_or_else(|| "Idle".to_string(), |order| order.description())
+        ));
+        if let Some(Destination(destination)) = destination {
+            lines.push(format!(
+                "Destination: ({:.1}, {:.1})",
+                destination.x, destination.z
+            ));
+        }
+
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+/// Clicking an icon narrows the selection to that unit, or with
+/// `SelectAllOfType` (Ctrl+click by default) to every selected unit of its type.
+fn selection_icon_click(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    icons: Query<(&Interaction, &SelectionIcon), Changed<Interaction>>,
+    selected: Query<(Entity, Option<&UnitType>), With<Selected>>,
+) {
+    for (interaction, icon) in &icons {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        let narrowed: Vec<Entity> = if actions.pressed(Action::SelectAllOfType) {
+            let clicked_type = selected.get(icon.unit).ok().and_then(|(_, t)| t);
+            selected
+                .iter()
+                .filter(|(_, unit_type)| *unit_type == clicked_type)
+                .map(|(unit, _)| unit)
+                .collect()
+        } else {
+            vec![icon.unit]
+        };
+
+        select_only(
+            &mut commands,
+            selected.iter().map(|(unit, _)| unit),
+            &narrowed,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::health_color;
+    use crate::constants::hud::{HEALTH_BAR_DAMAGED_COLOR, HEALTH_BAR_HEALTHY_COLOR};
+
+    #[test]
+    fn health_color_runs_from_damaged_to_healthy() {
+        assert_eq!(health_color(0.), HEALTH_BAR_DAMAGED_COLOR);
+        assert_eq!(health_color(1.), HEALTH_BAR_HEALTHY_COLOR);
+    }
+}
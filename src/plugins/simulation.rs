@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::research::TeamResearch,
+    constants::{
+        mechanics::{MOVE_COOLDOWN, ROTATION_SPEED},
+        units::UNITS_FILE,
+    },
+    systems::{
+        abilities::{ability_cooldown_system, use_abilities_system},
+        commands::{apply_unit_commands, finish_orders_system, CommandEvent},
+        definitions::apply_unit_definitions,
+        lifetime::lifetime_despawn_system,
+        spawn_plane::setup_board,
+        stats::{aura_system, stat_effects_system},
+    },
+    units::definitions::UnitDefinitions,
+    Game, GameState,
+};
+
+/// The game resource and board, state machine, unit definitions, what each team
+/// has researched, unit commands and the timed effects on units. Needs nothing
+/// but `MinimalPlugins`, so it can be driven from tests or a headless binary.
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Game>()
+            .insert_resource(UnitDefinitions::load_or_default(UNITS_FILE))
+            .init_resource::<TeamResearch>()
+            .add_event::<CommandEvent>()
+            .add_state(GameState::Playing)
+            .add_startup_system(setup_mechanics)
+            .add_system_set(SystemSet::on_enter(GameState::Playing).with_system(setup_board))
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(apply_unit_commands)
+                    .with_system(finish_orders_system)
+                    .with_system(apply_unit_definitions)
+                    .with_system(use_abilities_system)
+                    .with_system(ability_cooldown_system)
+                    .with_system(stat_effects_system)
+                    .with_system(aura_system.after(stat_effects_system))
+                    .with_system(lifetime_despawn_system),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::Playing).with_system(teardown))
+            .add_system_set(SystemSet::on_exit(GameState::GameOver).with_system(teardown));
+    }
+}
+
+fn setup_mechanics(mut game: ResMut<Game>) {
+    game.mechanics.move_cooldown = Timer::from_seconds(MOVE_COOLDOWN, TimerMode::Repeating);
+    game.mechanics.rotate_cooldown = Timer::from_seconds(ROTATION_SPEED, TimerMode::Repeating);
+    game.mechanics.direction.desired = (0.0 as f32).to_degrees();
+    game.mechanics.direction.current = game.mechanics.direction.desired;
+}
+
+// remove all entities that are not a camera
+fn teardown(mut commands: Commands, entities: Query<Entity, Without<Camera>>) {
+    for entity in &entities {
+        commands.entity(entity).despawn_recursive();
+    }
+}
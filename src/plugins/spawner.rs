@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{egui, EguiContext, EguiPlugin};
+use bevy_iso3d_rts_cursor_plugin::Cursor;
+
+use crate::{
+    components::{
+        buildings::Building,
+        input::{Action, ActionState},
+        mechanics::{Team, Unit},
+    },
+    constants::{minimap::TEAM_COLORS, units::SPAWN_SPACING},
+    systems::spawn_unit::{formation_positions, spawn_unit_at, SpawnLayout},
+    units::definitions::UnitDefinitions,
+    GameState,
+};
+
+/// A sandbox tool window for spawning batches of units and clearing them away.
+pub struct SpawnerPlugin;
+
+impl Plugin for SpawnerPlugin {
+    fn build(&self, app: &mut App) {
+        // The world inspector adds egui too; only the first one may.
+        if !app.world.contains_resource::<EguiContext>() {
+            app.add_plugin(EguiPlugin);
+        }
+        app.init_resource::<SpawnerSettings>().add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                .with_system(spawner_panel)
+                .with_system(spawn_on_click.after(spawner_panel)),
+        );
+    }
+}
+
+/// What the spawner panel spawns, for whom and how it is laid out.
+#[derive(Resource, Debug, Clone)]
+pub struct SpawnerSettings {
+    pub unit_type: String,
+    pub count: usize,
+    pub team: Team,
+    pub layout: SpawnLayout,
+    /// Spawn a batch wherever the spawn action is pressed on the map.
+    pub spawn_on_click: bool,
+}
+
+impl Default for SpawnerSettings {
+    fn default() -> Self {
+        Self {
+            unit_type: "ship".to_string(),
+            count: 10,
+            team: Team::PLAYER,
+            layout: SpawnLayout::Grid,
+            spawn_on_click: false,
+        }
+    }
+}
+
+fn spawn_batch(commands: &mut Commands, settings: &SpawnerSettings, center: Vec3) {
+    for position in formation_positions(settings.layout, settings.count, center, SPAWN_SPACING) {
+        spawn_unit_at(commands, &settings.unit_type, settings.team, position);
+    }
+}
+
+fn spawner_panel(
+    mut commands: Commands,
+    mut egui_context: ResMut<EguiContext>,
+    mut settings: ResMut<SpawnerSettings>,
+    definitions: Res<UnitDefinitions>,
+    cursor: Res<Cursor>,
+    units: Query<Entity, (With<Unit>, Without<Building>)>,
+) {
+    let mut unit_types: Vec<&String> = definitions.units.keys().collect();
+    unit_types.sort();
+
+    egui::Window::new("Spawner").show(egui_context.ctx_mut(), |ui| {
+        egui::ComboBox::from_label("Unit type")
+            .selected_text(settings.unit_type.clone())
+            .show_ui(ui, |ui| {
+                for unit_type in unit_types {
+                    ui.selectable_value(&mut settings.unit_type, unit_type.clone(), unit_type);
+                }
+            });
+        ui.add(egui::Slider::new(&mut settings.count, 1..=500).text("Count"));
+        egui::ComboBox::from_label("Owner")
+            .selected_text(format!("Team {}", settings.team.0))
+            .show_ui(ui, |ui| {
+                for team in 0..TEAM_COLORS.len() as u8 {
+                    ui.selectable_value(&mut settings.team, Team(team), format!("Team {}", team));
+                }
+            });
+        ui.horizontal(|ui| {
+            for layout in SpawnLayout::ALL {
+                ui.radio_value(&mut settings.layout, layout, layout.label());
+            }
+        });
+        ui.checkbox(&mut settings.spawn_on_click, "Spawn on click");
+
+        ui.horizontal(|ui| {
+            if ui.button("Spawn at cursor").clicked() {
+                spawn_batch(&mut commands, &settings, cursor.location.xyz);
+            }
+            if ui.button("Clear all units").clicked() {
+                for unit in &units {
+                    commands.entity(unit).despawn_recursive();
+                }
+            }
+        });
+        ui.label(format!("{} units", units.iter().len()));
+    });
+}
+
+fn spawn_on_click(
+    mut commands: Commands,
+    mut egui_context: ResMut<EguiContext>,
+    actions: Res<ActionState>,
+    cursor: Res<Cursor>,
+    settings: Res<SpawnerSettings>,
+) {
+    if !settings.spawn_on_click || !actions.just_pressed(Action::SpawnUnits) {
+        return;
+    }
+    // Clicks on the panel itself are not spawns.
+    if egui_context.ctx_mut().is_pointer_over_area() {
+        return;
+    }
+    spawn_batch(&mut commands, &settings, cursor.location.xyz);
+}
@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+
+use crate::{
+    systems::transport::{load_units_system, lost_cargo_system, unload_units_system},
+    GameState,
+};
+
+/// Loading units into transports and garrisons and setting them down again.
+/// Runs headless; `SelectionPlugin` hides and unselects loaded units.
+pub struct TransportPlugin;
+
+impl Plugin for TransportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                .with_system(load_units_system)
+                .with_system(unload_units_system)
+                .with_system(lost_cargo_system),
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use bevy::{
+    diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
+
+use crate::{
+    components::{
+        economy::Stockpiles,
+        input::{Action, ActionState},
+        mechanics::Team,
+    },
+    constants::{constants::SCREEN_WIDTH, hud::*},
+    GameState,
+};
+
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(FrameTimeDiagnosticsPlugin)
+            .add_startup_system(setup_scoreboard)
+            .add_startup_system(setup_stockpile_readout)
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(scoreboard_system)
+                    .with_system(stockpile_readout_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::GameOver).with_system(gameover_keyboard),
+            );
+    }
+}
+
+#[derive(Component)]
+pub struct Scoreboard;
+
+/// The player's banked resources.
+#[derive(Component)]
+pub struct StockpileReadout;
+
+fn setup_scoreboard(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // scoreboard
+    commands.spawn((
+        TextBundle::from_section(
+            "FPS:",
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 40.0,
+                color: Color::rgb(1., 0., 0.),
+            },
+        )
+        .with_style(Style {
+            // align_self: ,
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(10.),
+                left: Val::Px(SCREEN_WIDTH - 150.),
+                ..default()
+            },
+            ..default()
+        }),
+        Scoreboard,
+    ));
+}
+
+fn setup_stockpile_readout(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load(HUD_FONT),
+                font_size: HUD_FONT_SIZE * 1.5,
+                color: HUD_TEXT_COLOR,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(HUD_MARGIN),
+                left: Val::Px(HUD_MARGIN),
+                ..default()
+            },
+            ..default()
+        }),
+        StockpileReadout,
+    ));
+}
+
+fn stockpile_readout_system(
+    stockpiles: Res<Stockpiles>,
+    mut readouts: Query<&mut Text, With<StockpileReadout>>,
+) {
+    if !stockpiles.is_changed() {
+        return;
+    }
+    for mut text in &mut readouts {
+        text.sections[0].value = format!("Resources: {:.0}", stockpiles.get(Team::PLAYER));
+    }
+}
+
+fn scoreboard_system(mut query: Query<&mut Text, With<Scoreboard>>, diagnostics: Res<Diagnostics>) {
+    let mut text = query.single_mut();
+    let mut fps = 0.0;
+    if let Some(fps_diagnostic) = diagnostics.get(FrameTimeDiagnosticsPlugin::FPS) {
+        if let Some(fps_smoothed) = fps_diagnostic.smoothed() {
+            fps = fps_smoothed;
+
+            text.sections[0].value = format!("FPS: {}", fps.round());
+        }
+    }
+}
+
+// restart the game when pressing the restart action (spacebar by default)
+fn gameover_keyboard(mut state: ResMut<State<GameState>>, actions: Res<ActionState>) {
+    if actions.just_pressed(Action::Restart) {
+        state.set(GameState::Playing).unwrap();
+    }
+}
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::vision::FogOfWar,
+    systems::vision::{mark_fogged_system, reset_fog, update_vision_system},
+    GameState,
+};
+
+/// Per-team fog of war computed from unit sight ranges. Runs headless;
+/// `FogOverlayPlugin` draws it and hides what the player cannot see.
+pub struct VisionPlugin;
+
+impl Plugin for VisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FogOfWar>()
+            .add_system_set(SystemSet::on_enter(GameState::Playing).with_system(reset_fog))
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(update_vision_system)
+                    .with_system(mark_fogged_system.after(update_vision_system)),
+            );
+    }
+}
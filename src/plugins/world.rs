@@ -0,0 +1,103 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_scene_hook::HookPlugin;
+
+use crate::{
+    systems::{
+        economy::decorate_economy,
+        spawn_plane::{plane_setup, setup_board},
+        spawn_unit::decorate_units,
+    },
+    GameState,
+};
+
+/// The board, lighting, scene loading and economy and unit meshes.
+pub struct WorldPlugin;
+
+impl Plugin for WorldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(HookPlugin)
+            .add_startup_system(setup_lights)
+            .add_system_set(
+                SystemSet::on_enter(GameState::Playing).with_system(plane_setup.after(setup_board)),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    // .with_system(update_lights)
+                    .with_system(decorate_economy)
+                    .with_system(decorate_units),
+            );
+    }
+}
+
+fn setup_lights(mut commands: Commands) {
+    // Skelly
+    // commands.spawn((
+    //     SceneBundle {
+    //         // scene: asset_server.load("BACKROOMS_POLY_GAME.glb#Scene0"),
+    //         scene: asset_server.load("basic_toon_skeleton/scene.gltf#Scene0"),
+    //         transform: Transform {
+    //             translation: Vec3 {
+    //                 x: 2.,
+    //                 y: GROUND_LEVEL,
+    //                 z: 2.,
+    //             },
+    //             scale: Vec3::ONE * SKELLY_SCALE_MODIFIER,
+    //             ..default()
+    //         },
+    //         ..default()
+    //     },
+    //     Animated {
+    //         current_animation: 4,
+    //         animations: Animations(Some(vec![
+    //             asset_server.load("basic_toon_skeleton/scene.gltf#Animation0"),
+    //             asset_server.load("basic_toon_skeleton/scene.gltf#Animation1"),
+    //             asset_server.load("basic_toon_skeleton/scene.gltf#Animation2"),
+    //             asset_server.load("basic_toon_skeleton/scene.gltf#Animation3"),
+    //             asset_server.load("basic_toon_skeleton/scene.gltf#Animation4"),
+    //             asset_server.load("basic_toon_skeleton/scene.gltf#Animation5"),
+    //             asset_server.load("basic_toon_skeleton/scene.gltf#Animation6"),
+    //             asset_server.load("basic_toon_skeleton/scene.gltf#Animation7"),
+    //         ])),
+    //         animation_library: AnimationLibrary {
+    //             walk: 1,
+    //             run: 4,
+    //             idle: 0,
+    //             attack: 7,
+    //             spawn: 2,
+    //             alerted: 3,
+    //         },
+    //     },
+    //     Pickable,
+    //     MovementSpeed { value: 3.0 },
+    //     RotationSpeed { value: 0.5 },
+    //     Unit,
+    // ));
+
+    // directional 'sun' light
+    const HALF_SIZE: f32 = 10.0;
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            illuminance: 5900.0,
+            // Configure the projection to better fit the scene
+            shadow_projection: OrthographicProjection {
+                left: -HALF_SIZE,
+                right: HALF_SIZE,
+                bottom: -HALF_SIZE,
+                top: HALF_SIZE,
+                near: -10.0 * HALF_SIZE,
+                far: 70.0 * HALF_SIZE,
+                ..default()
+            },
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform {
+            translation: Vec3::new(0.0, 12.0, 0.0),
+            rotation: Quat::from_rotation_x(-PI / 4.),
+            ..default()
+        },
+        ..default()
+    });
+}
@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        mechanics::{AbilityCooldowns, Health, UnitType},
+        stats::{StatEffect, StatEffects},
+    },
+    systems::commands::{CommandEvent, UnitCommand},
+    units::definitions::{AbilityEffect, UnitDefinitions},
+};
+
+pub fn use_abilities_system(
+    mut events: EventReader<CommandEvent>,
+    definitions: Res<UnitDefinitions>,
+    mut units: Query<(
+        &UnitType,
+        &mut AbilityCooldowns,
+        Option<&mut Health>,
+        Option<&mut StatEffects>,
+    )>,
+) {
+    for event in events.iter() {
+        let UnitCommand::Ability(name) = &event.command else {
+            continue;
+        };
+
+        let mut iter = units.iter_many_mut(&event.units);
+        while let Some((unit_type, mut cooldowns, health, effects)) = iter.fetch_next() {
+            let Some(ability) = definitions.ability(&unit_type.0, name) else {
+                continue;
+            };
+            if !cooldowns.is_ready(name) {
+                continue;
+            }
+
+            match ability.effect {
+                AbilityEffect::Heal(amount) => {
+                    let Some(mut health) = health else {
+                        continue;
+                    };
+                    health.value = (health.value + amount).min(health.max);
+                }
+                AbilityEffect::Modify {
+                    stat,
+                    change,
+                    duration,
+                } => {
+                    let Some(mut effects) = effects else {
+                        continue;
+                    };
+                    effects.add(StatEffect::new(name, stat, change, duration));
+                }
+            }
+            cooldowns.start(name, ability.cooldown);
+        }
+    }
+}
+
+pub fn ability_cooldown_system(time: Res<Time>, mut units: Query<&mut AbilityCooldowns>) {
+    for mut cooldowns in &mut units {
+        cooldowns.tick(time.delta());
+    }
+}
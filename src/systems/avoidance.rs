@@ -0,0 +1,408 @@
+//! Optimal reciprocal collision avoidance (ORCA), after the RVO2 library:
+//! https://gamma.cs.unc.edu/RVO2/
+//!
+//! Every unit picks the velocity closest to the one it would like that does
+//! not collide with any neighbour within the time horizon, assuming each
+//! neighbour takes half of the effort to avoid it. Static obstacles take none,
+//! and are never given up on to make room for a neighbour. Works on the ground
+//! plane, with `Vec2`s holding `(x, z)`.
+
+use bevy::prelude::*;
+
+const EPSILON: f32 = 0.00001;
+/// Radians a unit turns its preferred velocity by when that velocity would run
+/// into a neighbour. Units meeting exactly head-on otherwise stop nose to nose;
+/// turning the same way sends them past on opposite sides.
+const SIDESTEP_BIAS: f32 = 0.05;
+
+/// A unit as its neighbours see it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Agent {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub radius: f32,
+}
+
+impl Agent {
+    pub fn new(position: Vec3, velocity: Vec3, radius: f32) -> Self {
+        Self {
+            position: Vec2::new(position.x, position.z),
+            velocity: Vec2::new(velocity.x, velocity.z),
+            radius,
+        }
+    }
+}
+
+/// Something that stands still and never makes way, such as a finished
+/// building.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StaticObstacle {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+impl StaticObstacle {
+    pub fn new(position: Vec3, radius: f32) -> Self {
+        Self {
+            position: Vec2::new(position.x, position.z),
+            radius,
+        }
+    }
+}
+
+/// A half-plane of allowed velocities: everything left of `direction` through
+/// `point`.
+#[derive(Debug, Clone, Copy)]
+struct Line {
+    point: Vec2,
+    direction: Vec2,
+}
+
+/// The velocity closest to `preferred`, no faster than `max_speed`, that keeps
+/// `agent` clear of `neighbours` and `obstacles` for `time_horizon` seconds.
+/// Units already overlapping are pushed apart within `delta_seconds`.
+pub fn avoid_neighbours(
+    agent: &Agent,
+    preferred: Vec3,
+    max_speed: f32,
+    neighbours: impl Iterator<Item = Agent>,
+    obstacles: &[StaticObstacle],
+    time_horizon: f32,
+    delta_seconds: f32,
+) -> Vec3 {
+    // Obstacle lines come first so that they are never relaxed.
+    let lines: Vec<Line> = obstacles
+        .iter()
+        .map(|obstacle| {
+            let other = Agent {
+                position: obstacle.position,
+                velocity: Vec2::ZERO,
+                radius: obstacle.radius,
+            };
+            orca_line(agent, &other, 1., time_horizon, delta_seconds)
+        })
+        .chain(neighbours.map(|other| orca_line(agent, &other, 0.5, time_horizon, delta_seconds)))
+        .collect();
+
+    let mut preferred_2d = Vec2::new(preferred.x, preferred.z);
+    let on_collision_course = lines[obstacles.len()..]
+        .iter()
+        .any(|line| line.direction.perp_dot(line.point - preferred_2d) > 0.);
+    if on_collision_course {
+        let (sin, cos) = SIDESTEP_BIAS.sin_cos();
+        preferred_2d = Vec2::new(
+            preferred_2d.x * cos + preferred_2d.y * sin,
+            preferred_2d.y * cos - preferred_2d.x * sin,
+        );
+    }
+    let mut result = Vec2::ZERO;
+    let failed_line = linear_program2(&lines, max_speed, preferred_2d, false, &mut result);
+    if failed_line < lines.len() {
+        linear_program3(&lines, obstacles.len(), failed_line, max_speed, &mut result);
+    }
+    Vec3::new(result.x, 0., result.y)
+}
+
+/// The half-plane of velocities for `agent` that avoid `other`, taking
+/// `responsibility` of the effort.
+fn orca_line(
+    agent: &Agent,
+    other: &Agent,
+    responsibility: f32,
+    time_horizon: f32,
+    delta_seconds: f32,
+) -> Line {
+    let relative_position = other.position - agent.position;
+    let relative_velocity = agent.velocity - other.velocity;
+    let distance_squared = relative_position.length_squared();
+    let combined_radius = agent.radius + other.radius;
+    let combined_radius_squared = combined_radius * combined_radius;
+
+    let (direction, u) = if distance_squared > combined_radius_squared {
+        // Vector from the cut-off centre to the relative velocity.
+        let w = relative_velocity - relative_position / time_horizon;
+        let w_length_squared = w.length_squared();
+        let dot = w.dot(relative_position);
+
+        if dot < 0. && dot * dot > combined_radius_squared * w_length_squared {
+            // Closest to the cut-off circle.
+            let w_length = w_length_squared.sqrt();
+            let unit_w = w / w_length;
+            (
+                Vec2::new(unit_w.y, -unit_w.x),
+                (combined_radius / time_horizon - w_length) * unit_w,
+            )
+        } else {
+            // Closest to one of the legs of the velocity obstacle.
+            let leg = (distance_squared - combined_radius_squared).sqrt();
+            let direction = if relative_position.perp_dot(w) > 0. {
+                Vec2::new(
+                    relative_position.x * leg - relative_position.y * combined_radius,
+                    relative_position.x * combined_radius + relative_position.y * leg,
+                ) / distance_squared
+            } else {
+                -Vec2::new(
+                    relative_position.x * leg + relative_position.y * combined_radius,
+                    -relative_position.x * combined_radius + relative_position.y * leg,
+                ) / distance_squared
+            };
+            let along = relative_velocity.dot(direction);
+            (direction, along * direction - relative_velocity)
+        }
+    } else {
+        // Already overlapping: get clear within this step.
+        let inverse_step = 1. / delta_seconds.max(EPSILON);
+        let w = relative_velocity - relative_position * inverse_step;
+        let w_length = w.length().max(EPSILON);
+        let unit_w = w / w_length;
+        (
+            Vec2::new(unit_w.y, -unit_w.x),
+            (combined_radius * inverse_step - w_length) * unit_w,
+        )
+    };
+
+    Line {
+        point: agent.velocity + responsibility * u,
+        direction,
+    }
+}
+
+/// Best velocity on line `line_index` that satisfies the lines before it.
+fn linear_program1(
+    lines: &[Line],
+    line_index: usize,
+    radius: f32,
+    optimal: Vec2,
+    optimize_direction: bool,
+    result: &mut Vec2,
+) -> bool {
+    let line = lines[line_index];
+    let dot = line.point.dot(line.direction);
+    let discriminant = dot * dot + radius * radius - line.point.length_squared();
+    if discriminant < 0. {
+        // The speed limit rules out the whole line.
+        return false;
+    }
+
+    let root = discriminant.sqrt();
+    let mut t_left = -dot - root;
+    let mut t_right = -dot + root;
+    for other in &lines[..line_index] {
+        let denominator = line.direction.perp_dot(other.direction);
+        let numerator = other.direction.perp_dot(line.point - other.point);
+        if denominator.abs() <= EPSILON {
+            // Parallel lines.
+            if numerator < 0. {
+                return false;
+            }
+            continue;
+        }
+        let t = numerator / denominator;
+        if denominator >= 0. {
+            t_right = t_right.min(t);
+        } else {
+            t_left = t_left.max(t);
+        }
+        if t_left > t_right {
+            return false;
+        }
+    }
+
+    let t = if optimize_direction {
+        if optimal.dot(line.direction) > 0. {
+            t_right
+        } else {
+            t_left
+        }
+    } else {
+        line.direction
+            .dot(optimal - line.point)
+            .clamp(t_left, t_right)
+    };
+    *result = line.point + t * line.direction;
+    true
+}
+
+/// Finds the velocity closest to `optimal` inside every line and the speed
+/// limit. Returns the index of the first line it could not satisfy, or the
+/// number of lines if it satisfied them all.
+fn linear_program2(
+    lines: &[Line],
+    radius: f32,
+    optimal: Vec2,
+    optimize_direction: bool,
+    result: &mut Vec2,
+) -> usize {
+    *result = if optimize_direction {
+        optimal * radius
+    } else if optimal.length_squared() > radius * radius {
+        optimal.normalize() * radius
+    } else {
+        optimal
+    };
+
+    for index in 0..lines.len() {
+        if lines[index]
+            .direction
+            .perp_dot(lines[index].point - *result)
+            > 0.
+        {
+            let previous = *result;
+            if !linear_program1(lines, index, radius, optimal, optimize_direction, result) {
+                *result = previous;
+                return index;
+            }
+        }
+    }
+    lines.len()
+}
+
+/// When the lines cannot all be met, finds the velocity that breaks them the
+/// least. The first `hard_lines` are kept whatever it costs.
+fn linear_program3(
+    lines: &[Line],
+    hard_lines: usize,
+    begin: usize,
+    radius: f32,
+    result: &mut Vec2,
+) {
+    let mut distance = 0.;
+    for index in begin..lines.len() {
+        let line = lines[index];
+        if line.direction.perp_dot(line.point - *result) <= distance {
+            continue;
+        }
+
+        let soft_lines = lines.get(hard_lines..index).unwrap_or_default();
+        let projected: Vec<Line> = lines[..hard_lines]
+            .iter()
+            .copied()
+            .chain(soft_lines.iter().filter_map(|other| {
+                let determinant = line.direction.perp_dot(other.direction);
+                let point = if determinant.abs() <= EPSILON {
+                    if line.direction.dot(other.direction) > 0. {
+                        // Same direction: the other line adds nothing.
+                        return None;
+                    }
+                    0.5 * (line.point + other.point)
+                } else {
+                    line.point
+                        + (other.direction.perp_dot(line.point - other.point) / determinant)
+                            * line.direction
+                };
+                Some(Line {
+                    point,
+                    direction: (other.direction - line.direction).normalize_or_zero(),
+                })
+            }))
+            .collect();
+
+        let previous = *result;
+        let optimal = Vec2::new(-line.direction.y, line.direction.x);
+        if linear_program2(&projected, radius, optimal, true, result) < projected.len() {
+            // Can only fail through rounding; keep the last answer.
+            *result = previous;
+        }
+        distance = line.direction.perp_dot(line.point - *result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{avoid_neighbours, Agent, StaticObstacle};
+
+    const STEP: f32 = 1. / 60.;
+
+    #[test]
+    fn without_neighbours_the_preferred_velocity_is_kept() {
+        let agent = Agent::new(Vec3::ZERO, Vec3::ZERO, 0.5);
+        let preferred = Vec3::new(1., 0., 2.);
+
+        let velocity = avoid_neighbours(&agent, preferred, 5., std::iter::empty(), &[], 1., STEP);
+
+        assert!(velocity.distance(preferred) < 0.001);
+    }
+
+    #[test]
+    fn two_units_heading_at_each_other_both_sidestep_and_never_touch() {
+        let mut left = Agent::new(Vec3::new(0., 0., 0.), Vec3::ZERO, 0.5);
+        let mut right = Agent::new(Vec3::new(10., 0., 0.), Vec3::ZERO, 0.5);
+        let mut closest = f32::MAX;
+
+        for _ in 0..600 {
+            let left_velocity = avoid_neighbours(
+                &left,
+                Vec3::new(2., 0., 0.),
+                2.,
+                std::iter::once(right),
+                &[],
+                2.,
+                STEP,
+            );
+            let right_velocity = avoid_neighbours(
+                &right,
+                Vec3::new(-2., 0., 0.),
+                2.,
+                std::iter::once(left),
+                &[],
+                2.,
+                STEP,
+            );
+            left.velocity = Vec2::new(left_velocity.x, left_velocity.z);
+            right.velocity = Vec2::new(right_velocity.x, right_velocity.z);
+            left.position += left.velocity * STEP;
+            right.position += right.velocity * STEP;
+            closest = closest.min(left.position.distance(right.position));
+        }
+
+        assert!(closest >= 1. - 0.01);
+        // They got past each other rather than stopping nose to nose.
+        assert!(left.position.x > right.position.x);
+    }
+
+    #[test]
+    fn units_go_around_static_obstacles() {
+        let mut agent = Agent::new(Vec3::ZERO, Vec3::ZERO, 0.5);
+        let obstacle = StaticObstacle::new(Vec3::new(5., 0., 0.3), 2.);
+        let mut closest = f32::MAX;
+
+        for _ in 0..600 {
+            let velocity = avoid_neighbours(
+                &agent,
+                Vec3::new(2., 0., 0.),
+                2.,
+                std::iter::empty(),
+                &[obstacle],
+                2.,
+                STEP,
+            );
+            agent.velocity = Vec2::new(velocity.x, velocity.z);
+            agent.position += agent.velocity * STEP;
+            closest = closest.min(agent.position.distance(obstacle.position));
+        }
+
+        assert!(closest >= 2.5 - 0.01);
+        assert!(agent.position.x > 10.);
+    }
+
+    #[test]
+    fn neighbours_out_of_the_way_leave_the_preferred_velocity_alone() {
+        let agent = Agent::new(Vec3::ZERO, Vec3::new(2., 0., 0.), 0.5);
+        let alongside = Agent::new(Vec3::new(0., 0., 3.), Vec3::new(2., 0., 0.), 0.5);
+        let preferred = Vec3::new(2., 0., 0.);
+
+        let velocity = avoid_neighbours(
+            &agent,
+            preferred,
+            2.,
+            std::iter::once(alongside),
+            &[],
+            2.,
+            STEP,
+        );
+
+        assert!(velocity.distance(preferred) < 0.001);
+    }
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        buildings::{Building, BuildingDefinitions, ProductionQueue, UnderConstruction},
+        economy::{Depot, Stockpiles},
+        mechanics::{Health, Team, Unit, UnitType},
+        transport::Transport,
+    },
+    Game,
+};
+
+/// Asks for a building of `kind` on the cell under `position`, paid for by
+/// `team`.
+pub struct PlaceBuildingEvent {
+    pub kind: String,
+    pub team: Team,
+    pub position: Vec3,
+}
+
+pub fn place_buildings_system(
+    mut commands: Commands,
+    mut events: EventReader<PlaceBuildingEvent>,
+    definitions: Res<BuildingDefinitions>,
+    mut game: ResMut<Game>,
+    mut stockpiles: ResMut<Stockpiles>,
+) {
+    for event in events.iter() {
+        let Some(definition) = definitions.get(&event.kind) else {
+            warn!("No definition for building {:?}", event.kind);
+            continue;
+        };
+        let Some(origin) = game.cell_index(event.position) else {
+            continue;
+        };
+        if let Err(error) = game.check_placement(origin, definition.footprint) {
+            info!("Cannot place {} there: {:?}", event.kind, error);
+            continue;
+        }
+        if !stockpiles.try_spend(event.team, definition.cost) {
+            info!("Not enough resources for {}", event.kind);
+            continue;
+        }
+
+        let cells = Game::footprint(origin, definition.footprint);
+        let building = commands
+            .spawn((
+                TransformBundle::from_transform(Transform::from_translation(
+                    Game::footprint_center(origin, definition.footprint),
+                )),
+                Unit,
+                event.team,
+                UnitType(event.kind.clone()),
+                Health::new(definition.health),
+                Building {
+                    kind: event.kind.clone(),
+                    cells: cells.clone(),
+                },
+                UnderConstruction {
+                    timer: Timer::from_seconds(definition.build_time, TimerMode::Once),
+                },
+                Name::new(event.kind.clone()),
+            ))
+            .id();
+        // Reserved straight away so nothing else is placed on top while it is built.
+        for index in cells {
+            if let Some(cell) = game.cell_mut(index) {
+                cell.occupant = Some(building);
+            }
+        }
+    }
+}
+
+/// Finishes buildings whose timer is up: their cells become blocked, depots
+/// start accepting cargo, producers start taking orders and garrisons open.
+pub fn construction_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    definitions: Res<BuildingDefinitions>,
+    mut game: ResMut<Game>,
+    mut buildings: Query<(Entity, &Building, &mut UnderConstruction)>,
+) {
+    for (entity, building, mut construction) in &mut buildings {
+        if !construction.timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        commands.entity(entity).remove::<UnderConstruction>();
+        for index in &building.cells {
+            if let Some(cell) = game.cell_mut(*index) {
+                cell.blocked = true;
+            }
+        }
+        let Some(definition) = definitions.get(&building.kind) else {
+            continue;
+        };
+        if definition.depot {
+            commands.entity(entity).insert(Depot);
+        }
+        if !definition.produces.is_empty() {
+            commands.entity(entity).insert(ProductionQueue::default());
+        }
+        if definition.garrison > 0 {
+            commands
+                .entity(entity)
+                .insert(Transport::new(definition.garrison));
+        }
+    }
+}
+
+/// Frees the cells of buildings that were destroyed.
+pub fn release_cells_system(mut game: ResMut<Game>, buildings: Query<(), With<Building>>) {
+    for cell in game.board.iter_mut().flatten() {
+        if cell.occupant.map_or(false, |occupant| !buildings.contains(occupant)) {
+            cell.occupant = None;
+            cell.blocked = false;
+        }
+    }
+}
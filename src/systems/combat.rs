@@ -0,0 +1,263 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::ExternalImpulse;
+
+use crate::{
+    components::{
+        mechanics::{Destination, Health, Rotating, Splash, Team, Unit, Weapon},
+        orders::Order,
+        stats::{effective, Stat, StatEffects},
+    },
+    constants::units::ACQUISITION_RADIUS,
+    systems::{commands::approach, line_of_sight::LineOfSight},
+    util::ground_distance,
+};
+
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    /// Whoever dealt the damage, if anyone.
+    pub source: Option<Entity>,
+}
+
+/// Damage to every unit within `radius` of `center`: all of `damage` at the
+/// center, down to `1 - falloff` of it at the edge. Physics bodies in range are
+/// pushed away from the center with up to `impulse`.
+pub struct AreaDamageEvent {
+    pub center: Vec3,
+    pub radius: f32,
+    pub damage: f32,
+    pub falloff: f32,
+    pub impulse: f32,
+    pub source: Option<Entity>,
+    /// Units of this team are left unhurt, if set.
+    pub spares: Option<Team>,
+}
+
+/// The share of an area's damage and impulse that reaches `distance` from its
+/// center.
+pub fn falloff_scale(distance: f32, radius: f32, falloff: f32) -> f32 {
+    if radius <= 0. {
+        return 1.;
+    }
+    1. - falloff.clamp(0., 1.) * (distance / radius).clamp(0., 1.)
+}
+
+/// Turns area damage into a `DamageEvent` for each unit caught in it, so it is
+/// applied, and answered by guards, like any other hit.
+pub fn area_damage_system(
+    mut events: EventReader<AreaDamageEvent>,
+    units: Query<(Entity, &Transform, Option<&Team>), (With<Unit>, With<Health>)>,
+    mut bodies: Query<(&Transform, &mut ExternalImpulse)>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for area in events.iter() {
+        let caught = units.iter().filter_map(|(unit, transform, team)| {
+            let distance = ground_distance(area.center, transform.translation);
+            let spared = area.spares == Some(team.copied().unwrap_or_default());
+            (distance <= area.radius && !spared).then_some((unit, distance))
+        });
+        for (unit, distance) in caught {
+            damage_events.send(DamageEvent {
+                target: unit,
+                amount: area.damage * falloff_scale(distance, area.radius, area.falloff),
+                source: area.source,
+            });
+        }
+
+        if area.impulse <= 0. {
+            continue;
+        }
+        for (transform, mut impulse) in &mut bodies {
+            let offset = (transform.translation - area.center) * Vec3::new(1., 0., 1.);
+            let distance = offset.length();
+            if distance > area.radius {
+                continue;
+            }
+            impulse.impulse += offset.normalize_or_zero()
+                * area.impulse
+                * falloff_scale(distance, area.radius, area.falloff);
+        }
+    }
+}
+
+pub fn apply_damage_system(
+    mut damage_events: EventReader<DamageEvent>,
+    mut units: Query<&mut Health>,
+) {
+    for damage in damage_events.iter() {
+        if let Ok(mut health) = units.get_mut(damage.target) {
+            health.value -= damage.amount;
+        }
+    }
+}
+
+pub fn death_system(mut commands: Commands, units: Query<(Entity, &Health), Changed<Health>>) {
+    for (entity, health) in &units {
+        if health.value <= 0. {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Fires weapons that are off cooldown. Attacking units, and guards and
+/// attack-moving units engaging an enemy, chase their target until it is in
+/// range and in sight; idle and holding units, and guards with nothing to
+/// engage, shoot the nearest enemy they can see in range but stay where they
+/// are. Attack-moving units engage the nearest enemy they can see within the
+/// acquisition radius and let it go once it gets away. Splash weapons burst
+/// where their target stands instead of hitting it alone.
+pub fn weapon_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut attackers: Query<(
+        Entity,
+        &Transform,
+        &mut Weapon,
+        &mut Order,
+        Option<&Team>,
+        Option<&Destination>,
+        Option<&StatEffects>,
+        Option<&Splash>,
+    )>,
+    targets: Query<(Entity, &Transform, Option<&Team>), (With<Unit>, With<Health>)>,
+    line_of_sight: LineOfSight,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut area_damage_events: EventWriter<AreaDamageEvent>,
+) {
+    let obstacles = line_of_sight.obstacles();
+    for (attacker, transform, mut weapon, mut order, team, destination, effects, splash) in
+        &mut attackers
+    {
+        weapon.cooldown.tick(time.delta());
+        let position = transform.translation;
+        let team = team.copied().unwrap_or_default();
+        let range = effective(effects, Stat::Range, weapon.range);
+        let acquisition_radius = range.max(ACQUISITION_RADIUS);
+        let nearest_enemy = |radius: f32| {
+            targets
+                .iter()
+                .filter(|(_, _, other_team)| other_team.copied().unwrap_or_default() != team)
+                .map(|(target, target_transform, _)| {
+                    let target_position = target_transform.translation;
+                    (
+                        target,
+                        target_position,
+                        ground_distance(position, target_position),
+                    )
+                })
+                .filter(|(_, target_position, distance)| {
+                    *distance <= radius
+                        && line_of_sight.can_see_past(&obstacles, position, *target_position)
+                })
+                .min_by(|a, b| a.2.total_cmp(&b.2))
+                .map(|(target, ..)| target)
+        };
+
+        let target = match *order {
+            Order::Attack(target)
+            | Order::Guard {
+                engaging: Some(target),
+                ..
+            }
+            | Order::AttackMove {
+                engaging: Some(target),
+                ..
+            } => {
+                let attack_moving = matches!(*order, Order::AttackMove { .. });
+                let target_position = targets
+                    .get(target)
+                    .ok()
+                    .map(|(_, target_transform, _)| target_transform.translation)
+                    .filter(|target_position| {
+                        !attack_moving
+                            || ground_distance(position, *target_position) <= acquisition_radius
+                    });
+                let Some(target_position) = target_position else {
+                    // Guards go back to guarding once their attacker is gone,
+                    // and attack-moves carry on with the move.
+                    match &mut *order {
+                        Order::Guard { engaging, .. } | Order::AttackMove { engaging, .. } => {
+                            *engaging = None
+                        }
+                        order => *order = Order::Idle,
+                    }
+                    commands
+                        .entity(attacker)
+                        .remove::<Destination>()
+                        .remove::<Rotating>();
+                    continue;
+                };
+
+                if ground_distance(position, target_position) > range
+                    || !line_of_sight.can_see_past(&obstacles, position, target_position)
+                {
+                    approach(&mut commands, attacker, destination, target_position);
+                    continue;
+                }
+                if destination.is_some() {
+                    commands.entity(attacker).remove::<Destination>();
+                }
+                target
+            }
+            Order::AttackMove {
+                destination,
+                engaging: None,
+            } => {
+                if let Some(enemy) = nearest_enemy(acquisition_radius) {
+                    *order = Order::AttackMove {
+                        destination,
+                        engaging: Some(enemy),
+                    };
+                }
+                continue;
+            }
+            Order::Idle | Order::Hold | Order::Guard { engaging: None, .. } => {
+                if !weapon.cooldown.finished() {
+                    continue;
+                }
+                let Some(target) = nearest_enemy(range) else {
+                    continue;
+                };
+                target
+            }
+            _ => continue,
+        };
+
+        if weapon.cooldown.finished() {
+            let damage = effective(effects, Stat::Damage, weapon.damage);
+            let target_position = targets
+                .get(target)
+                .map(|(_, target_transform, _)| target_transform.translation);
+            match (splash, target_position) {
+                (Some(splash), Ok(center)) => area_damage_events.send(AreaDamageEvent {
+                    center,
+                    radius: splash.radius,
+                    damage,
+                    falloff: splash.falloff,
+                    impulse: splash.impulse,
+                    source: Some(attacker),
+                    spares: Some(team),
+                }),
+                _ => damage_events.send(DamageEvent {
+                    target,
+                    amount: damage,
+                    source: Some(attacker),
+                }),
+            }
+            weapon.cooldown.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::falloff_scale;
+
+    #[test]
+    fn area_damage_falls_off_toward_the_edge() {
+        assert_eq!(falloff_scale(0., 4., 0.75), 1.);
+        assert_eq!(falloff_scale(2., 4., 0.75), 0.625);
+        assert_eq!(falloff_scale(4., 4., 0.75), 0.25);
+        assert_eq!(falloff_scale(3., 4., 0.), 1.);
+    }
+}
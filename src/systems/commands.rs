@@ -0,0 +1,262 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        economy::Harvester,
+        mechanics::{Destination, Detour, MovementSpeed, Rotating, Weapon},
+        orders::Order,
+        transport::Transport,
+    },
+    constants::units::{ARRIVAL_TOLERANCE, GUARD_DISTANCE},
+    util::are_positions_near,
+};
+
+/// An order given to one or more units, independent of where it came from
+/// (mouse, keyboard, UI or a test).
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitCommand {
+    Move(Vec3),
+    Stop,
+    Hold,
+    Attack(Entity),
+    /// Move to a point, engaging enemies met on the way.
+    AttackMove(Vec3),
+    /// Patrol between where each unit is now and the given point.
+    Patrol(Vec3),
+    /// Add a point to the end of each unit's patrol, or start one from where
+    /// it is if it was not patrolling.
+    AddPatrolPoint(Vec3),
+    /// Follow a friendly unit and fight off its attackers.
+    Guard(Entity),
+    /// Harvest from a resource node.
+    Gather(Entity),
+    /// Take nearby friendly units into a transport or building.
+    Load,
+    /// Carry the cargo to a point and set it down around it.
+    Unload(Vec3),
+    /// Use the named ability from the unit's definition.
+    Ability(String),
+    /// Queue a unit of the named type at a production building.
+    Produce(String),
+    /// Drop the last queued unit and refund it.
+    CancelProduction,
+    /// Start the named research at a building.
+    Research(String),
+}
+
+pub struct CommandEvent {
+    pub units: Vec<Entity>,
+    pub command: UnitCommand,
+}
+
+pub fn apply_unit_commands(
+    mut commands: Commands,
+    mut events: EventReader<CommandEvent>,
+    transforms: Query<&Transform>,
+    orders: Query<&Order>,
+    capabilities: Query<(
+        Option<&MovementSpeed>,
+        Option<&Weapon>,
+        Option<&Harvester>,
+        Option<&Transport>,
+    )>,
+) {
+    for event in events.iter() {
+        for unit in &event.units {
+            // The unit may have died between the command being issued and applied.
+            let Some(mut unit) = commands.get_entity(*unit) else {
+                continue;
+            };
+            // Units that cannot carry out an order keep the one they have.
+            // Moves given to buildings set their rally point instead.
+            let (speed, weapon, harvester, transport) =
+                capabilities.get(unit.id()).unwrap_or_default();
+            let capable = match event.command {
+                UnitCommand::Move(_)
+                | UnitCommand::AttackMove(_)
+                | UnitCommand::Patrol(_)
+                | UnitCommand::AddPatrolPoint(_)
+                | UnitCommand::Guard(_) => speed.is_some(),
+                UnitCommand::Attack(_) => weapon.is_some(),
+                UnitCommand::Gather(_) => harvester.is_some(),
+                UnitCommand::Load | UnitCommand::Unload(_) => transport.is_some(),
+                _ => true,
+            };
+            if !capable {
+                continue;
+            }
+            // A new order ends any detour taken on the way to the last one.
+            if !matches!(
+                event.command,
+                UnitCommand::Ability(_)
+                    | UnitCommand::Produce(_)
+                    | UnitCommand::CancelProduction
+                    | UnitCommand::Research(_)
+                    | UnitCommand::Load
+            ) {
+                unit.remove::<Detour>();
+            }
+
+            match event.command {
+                UnitCommand::Move(destination) => {
+                    unit.insert(Destination(destination))
+                        .insert(Rotating)
+                        .insert(Order::Move(destination));
+                }
+                UnitCommand::Stop => {
+                    unit.remove::<Destination>()
+                        .remove::<Rotating>()
+                        .insert(Order::Idle);
+                }
+                UnitCommand::Hold => {
+                    unit.remove::<Destination>()
+                        .remove::<Rotating>()
+                        .insert(Order::Hold);
+                }
+                // The combat systems walk the unit into range.
+                UnitCommand::Attack(target) => {
+                    unit.remove::<Destination>().insert(Order::Attack(target));
+                }
+                // The combat systems stop the unit for enemies on the way.
+                UnitCommand::AttackMove(destination) => {
+                    unit.insert(Destination(destination))
+                        .insert(Rotating)
+                        .insert(Order::AttackMove {
+                            destination,
+                            engaging: None,
+                        });
+                }
+                UnitCommand::Gather(node) => {
+                    unit.remove::<Destination>().insert(Order::Gather(node));
+                }
+                UnitCommand::AddPatrolPoint(point) => {
+                    if let Ok(Order::Patrol { points, next }) = orders.get(unit.id()) {
+                        let mut points = points.clone();
+                        points.push(point);
+                        unit.insert(Order::Patrol {
+                            points,
+                            next: *next,
+                        });
+                        continue;
+                    }
+                    let Ok(transform) = transforms.get(unit.id()) else {
+                        continue;
+                    };
+                    unit.insert(Destination(point))
+                        .insert(Rotating)
+                        .insert(Order::Patrol {
+                            points: vec![transform.translation, point],
+                            next: 1,
+                        });
+                }
+                UnitCommand::Patrol(to) => {
+                    let Ok(transform) = transforms.get(unit.id()) else {
+                        continue;
+                    };
+                    unit.insert(Destination(to))
+                        .insert(Rotating)
+                        .insert(Order::Patrol {
+                            points: vec![transform.translation, to],
+                            next: 1,
+                        });
+                }
+                // Guards keep to the side of their ward they were on.
+                UnitCommand::Guard(ward) => {
+                    let (Ok(transform), Ok(ward_transform)) =
+                        (transforms.get(unit.id()), transforms.get(ward))
+                    else {
+                        continue;
+                    };
+                    if ward == unit.id() {
+                        continue;
+                    }
+                    let mut offset = transform.translation - ward_transform.translation;
+                    offset.y = 0.;
+                    let offset = offset.try_normalize().unwrap_or(Vec3::X) * GUARD_DISTANCE;
+                    unit.remove::<Destination>().insert(Order::Guard {
+                        ward,
+                        offset,
+                        engaging: None,
+                    });
+                }
+                // Buildings let their garrison out where they stand.
+                UnitCommand::Unload(point) => {
+                    if speed.is_some() {
+                        unit.insert(Destination(point)).insert(Rotating);
+                    }
+                    unit.insert(Order::Unload(point));
+                }
+                // Abilities, production, research and loading leave the
+                // current order alone.
+                UnitCommand::Ability(_)
+                | UnitCommand::Produce(_)
+                | UnitCommand::CancelProduction
+                | UnitCommand::Research(_)
+                | UnitCommand::Load => {}
+            }
+        }
+    }
+}
+
+/// Heads `unit` for `target`, leaving its `Destination` alone if it is already
+/// going there so a chase does not restart rotation every frame.
+pub fn approach(
+    commands: &mut Commands,
+    unit: Entity,
+    current: Option<&Destination>,
+    target: Vec3,
+) {
+    let already_going = current.map_or(false, |Destination(destination)| {
+        are_positions_near(destination, &target, ARRIVAL_TOLERANCE)
+    });
+    if !already_going {
+        commands
+            .entity(unit)
+            .insert(Destination(target))
+            .insert(Rotating);
+    }
+}
+
+/// A move order is done once the unit has arrived and lost its `Destination`.
+/// Patrols head for their next point instead, units back from a detour carry
+/// on, and attack-moves done fighting resume the move unless already there.
+pub fn finish_orders_system(
+    mut commands: Commands,
+    mut units: Query<(Entity, &Transform, &mut Order, Option<&Detour>), Without<Destination>>,
+) {
+    for (unit, transform, mut order, detour) in &mut units {
+        if let Some(detour) = detour {
+            commands
+                .entity(unit)
+                .insert(Destination(detour.resume))
+                .insert(Rotating)
+                .remove::<Detour>();
+            continue;
+        }
+        match *order {
+            Order::Move(_) => *order = Order::Idle,
+            Order::AttackMove {
+                destination,
+                engaging: None,
+            } => {
+                if are_positions_near(&destination, &transform.translation, ARRIVAL_TOLERANCE) {
+                    *order = Order::Idle;
+                } else {
+                    commands
+                        .entity(unit)
+                        .insert(Destination(destination))
+                        .insert(Rotating);
+                }
+            }
+            Order::Patrol { .. } => {
+                if let Some(point) = order.next_patrol_point() {
+                    commands
+                        .entity(unit)
+                        .insert(Destination(point))
+                        .insert(Rotating);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        buildings::Building,
+        economy::Harvester,
+        mechanics::{
+            AbilityCooldowns, Health, MovementSpeed, RotationSpeed, Splash, StuckDetection, Team,
+            UnitType, Weapon,
+        },
+        research::TeamResearch,
+        stats::{Aura, StatEffects},
+        transport::Transport,
+        vision::SightRange,
+    },
+    systems::{movement::physics_body, research::UnitStats},
+    units::definitions::{MovementMode, UnitDefinitions},
+};
+
+/// Gives newly typed units the stats, weapon, abilities, harvesting, sight,
+/// movement mode, stuck thresholds, cargo hold and aura of their definition,
+/// with their team's research on top.
+/// Units whose type is not defined keep whatever they were spawned with.
+pub fn apply_unit_definitions(
+    mut commands: Commands,
+    definitions: Res<UnitDefinitions>,
+    research: Res<TeamResearch>,
+    units: Query<(Entity, &UnitType, Option<&Team>), (Added<UnitType>, Without<Building>)>,
+) {
+    for (unit, unit_type, team) in &units {
+        let Some(definition) = definitions.get(&unit_type.0) else {
+            warn!("No definition for unit type {:?}", unit_type.0);
+            continue;
+        };
+        let modifiers = research.modifiers(team.copied().unwrap_or_default());
+        let stats = UnitStats::new(definition, &unit_type.0, &modifiers);
+
+        let mut unit = commands.entity(unit);
+        unit.insert(Health::new(stats.health))
+            .insert(MovementSpeed { value: stats.speed })
+            .insert(RotationSpeed {
+                value: stats.rotation_speed,
+            })
+            .insert(SightRange {
+                value: stats.sight_range,
+            })
+            .insert(StatEffects::default());
+        if let (Some(weapon), Some(damage), Some(range)) =
+            (&definition.weapon, stats.damage, stats.range)
+        {
+            unit.insert(Weapon::new(damage, range, weapon.cooldown));
+            if let Some(splash) = &weapon.splash {
+                unit.insert(Splash {
+                    radius: splash.radius,
+                    falloff: splash.falloff,
+                    impulse: splash.impulse,
+                });
+            }
+        }
+        if let Some(harvester) = &definition.harvester {
+            unit.insert(Harvester {
+                gather_rate: harvester.gather_rate,
+                capacity: harvester.capacity,
+                ..default()
+            });
+        }
+        if let Some(stuck) = &definition.stuck {
+            unit.insert(StuckDetection::new(
+                stuck.timeout,
+                stuck.min_progress,
+                stuck.repaths,
+                stuck.nudges,
+            ));
+        }
+        if let Some(transport) = &definition.transport {
+            unit.insert(Transport::new(transport.capacity));
+        }
+        if let Some(aura) = &definition.aura {
+            unit.insert(Aura {
+                name: aura.name.clone(),
+                radius: aura.radius,
+                stat: aura.stat,
+                change: aura.change,
+            });
+        }
+        if definition.movement == MovementMode::Physics {
+            unit.insert(physics_body());
+        }
+        if !definition.abilities.is_empty() {
+            unit.insert(AbilityCooldowns::default());
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use bevy::{pbr::NotShadowCaster, prelude::*};
+
+use crate::{
+    components::{
+        buildings::Building,
+        economy::{Depot, EconomyConfig, Harvester, ResourceNode, Stockpiles},
+        mechanics::{Destination, Rotating, Team},
+        orders::Order,
+    },
+    constants::{constants::GROUND_LEVEL, economy::*},
+    systems::commands::approach,
+    util::ground_distance,
+};
+
+/// Places the configured resource nodes and depots and hands every team with
+/// a depot its starting resources.
+pub fn setup_economy(
+    mut commands: Commands,
+    config: Res<EconomyConfig>,
+    mut stockpiles: ResMut<Stockpiles>,
+) {
+    *stockpiles = Stockpiles::default();
+
+    for node in &config.nodes {
+        commands.spawn((
+            TransformBundle::from_transform(Transform::from_xyz(node.x, GROUND_LEVEL, node.z)),
+            ResourceNode {
+                remaining: node.amount,
+            },
+            Name::new("ResourceNode"),
+        ));
+    }
+
+    for depot in &config.depots {
+        let team = Team(depot.team);
+        stockpiles.add(team, config.starting_resources);
+        commands.spawn((
+            TransformBundle::from_transform(Transform::from_xyz(depot.x, GROUND_LEVEL, depot.z)),
+            Depot,
+            team,
+            Name::new(format!("Depot-{}", depot.team)),
+        ));
+    }
+}
+
+/// Gives nodes and depots a mesh. Kept apart from `setup_economy` so the
+/// economy also runs without rendering.
+pub fn decorate_economy(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    nodes: Query<Entity, Added<ResourceNode>>,
+    // Finished depot buildings already have their own mesh.
+    depots: Query<(Entity, Option<&Team>), (Added<Depot>, Without<Building>)>,
+) {
+    for node in &nodes {
+        commands.entity(node).insert((
+            meshes.add(Mesh::from(shape::Icosphere {
+                radius: RESOURCE_NODE_SIZE / 2.,
+                subdivisions: 1,
+            })),
+            materials.add(RESOURCE_NODE_COLOR.into()),
+            VisibilityBundle::default(),
+            NotShadowCaster,
+        ));
+    }
+
+    for (depot, team) in &depots {
+        commands.entity(depot).insert((
+            meshes.add(Mesh::from(shape::Cube { size: DEPOT_SIZE })),
+            materials.add(team.copied().unwrap_or_default().color().into()),
+            VisibilityBundle::default(),
+        ));
+    }
+}
+
+fn stop(commands: &mut Commands, unit: Entity, destination: Option<&Destination>) {
+    if destination.is_some() {
+        commands
+            .entity(unit)
+            .remove::<Destination>()
+            .remove::<Rotating>();
+    }
+}
+
+/// Walks gathering harvesters between their node and the nearest depot of
+/// their team. A harvester whose node runs out moves on to the nearest node
+/// within the search radius, or unloads and goes idle if there is none.
+pub fn harvest_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<EconomyConfig>,
+    mut stockpiles: ResMut<Stockpiles>,
+    mut harvesters: Query<(
+        Entity,
+        &Transform,
+        &mut Harvester,
+        &mut Order,
+        Option<&Team>,
+        Option<&Destination>,
+    )>,
+    mut nodes: Query<(Entity, &Transform, &mut ResourceNode), Without<Harvester>>,
+    depots: Query<(&Transform, Option<&Team>), With<Depot>>,
+) {
+    for (unit, transform, mut harvester, mut order, team, destination) in &mut harvesters {
+        let Order::Gather(node) = *order else {
+            continue;
+        };
+        let position = transform.translation;
+        let team = team.copied().unwrap_or_default();
+
+        if !harvester.returning {
+            match nodes.get_mut(node) {
+                Ok((_, node_transform, mut resources)) => {
+                    let node_position = node_transform.translation;
+                    if ground_distance(position, node_position) > config.gather_range {
+                        approach(&mut commands, unit, destination, node_position);
+                        continue;
+                    }
+                    stop(&mut commands, unit, destination);
+
+                    let space = harvester.capacity - harvester.cargo;
+                    let taken = (harvester.gather_rate * time.delta_seconds())
+                        .min(space)
+                        .min(resources.remaining);
+                    resources.remaining -= taken;
+                    harvester.cargo += taken;
+                    if taken > 0. && resources.remaining <= 0. {
+                        commands.entity(node).despawn_recursive();
+                    }
+                    harvester.returning = harvester.cargo >= harvester.capacity;
+                }
+                Err(_) => {
+                    let next = nodes
+                        .iter()
+                        .filter(|(_, _, resources)| resources.remaining > 0.)
+                        .map(|(next, next_transform, _)| {
+                            (next, ground_distance(position, next_transform.translation))
+                        })
+                        .filter(|(_, distance)| *distance <= config.search_radius)
+                        .min_by(|a, b| a.1.total_cmp(&b.1));
+                    if let Some((next, _)) = next {
+                        *order = Order::Gather(next);
+                    } else if harvester.cargo > 0. {
+                        harvester.returning = true;
+                    } else {
+                        *order = Order::Idle;
+                        stop(&mut commands, unit, destination);
+                    }
+                }
+            }
+        }
+
+        if !harvester.returning {
+            continue;
+        }
+        let depot = depots
+            .iter()
+            .filter(|(_, depot_team)| depot_team.copied().unwrap_or_default() == team)
+            .map(|(depot_transform, _)| depot_transform.translation)
+            .min_by(|a, b| ground_distance(position, *a).total_cmp(&ground_distance(position, *b)));
+        let Some(depot) = depot else {
+            // Nowhere to unload; wait with a full hold until a depot exists.
+            stop(&mut commands, unit, destination);
+            continue;
+        };
+        if ground_distance(position, depot) > config.depot_range {
+            approach(&mut commands, unit, destination, depot);
+            continue;
+        }
+
+        stockpiles.add(team, harvester.cargo);
+        harvester.cargo = 0.;
+        harvester.returning = false;
+        stop(&mut commands, unit, destination);
+    }
+}
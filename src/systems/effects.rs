@@ -1,44 +1,178 @@
 use bevy::prelude::*;
 
-use crate::{components::effects::Blinker, util::map_value_to_range, Game};
+use crate::{
+    components::{
+        effects::{
+            lerp_color, Easing, EffectMaterials, Tween, TweenCompleted, TweenProperty, Tweens,
+        },
+        mechanics::Selected,
+    },
+    constants::effects::*,
+    systems::combat::DamageEvent,
+};
 
-pub fn blink_system(
+/// Gives entities about to tween a material their own copies of the materials
+/// on them and their descendants. Scenes that have not spawned their meshes yet
+/// are tried again next frame.
+pub fn instance_effect_materials(
     mut commands: Commands,
-    mut game: ResMut<Game>,
-    mut blinkers: Query<(Entity, &mut Handle<StandardMaterial>, &mut Blinker), With<Blinker>>,
+    tweened: Query<(Entity, &Tweens), Without<EffectMaterials>>,
+    children: Query<&Children>,
+    mut handles: Query<&mut Handle<StandardMaterial>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, tweens) in &tweened {
+        if !tweens.uses_material() {
+            continue;
+        }
+        let fades = tweens
+            .tweens
+            .iter()
+            .any(|tween| matches!(tween.property, TweenProperty::Alpha { .. }));
+
+        let mut instances = Vec::new();
+        let mut emissive = Vec::new();
+        let mut stack = vec![entity];
+        while let Some(next) = stack.pop() {
+            if let Ok(descendants) = children.get(next) {
+                stack.extend(descendants.iter());
+            }
+            let Ok(mut handle) = handles.get_mut(next) else {
+                continue;
+            };
+            let Some(mut material) = materials.get(&*handle).cloned() else {
+                continue;
+            };
+            if fades {
+                material.alpha_mode = AlphaMode::Blend;
+            }
+            emissive.push(material.emissive);
+            *handle = materials.add(material);
+            instances.push(handle.clone());
+        }
+        if !instances.is_empty() {
+            commands.entity(entity).insert(EffectMaterials {
+                materials: instances,
+                emissive,
+            });
+        }
+    }
+}
+
+/// Advances every tween by the frame's time and applies it, then drops the
+/// finished ones and reports them with `TweenCompleted`.
+pub fn tween_system(
     time: Res<Time>,
+    mut tweened: Query<(
+        Entity,
+        &mut Tweens,
+        Option<&mut Transform>,
+        Option<&EffectMaterials>,
+    )>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut completed: EventWriter<TweenCompleted>,
 ) {
-    if game.mechanics.move_cooldown.tick(time.delta()).finished() {
-        for (entity, material, mut blinker) in &mut blinkers {
-            if let Some(material) = materials.get_mut(&material) {
-                blinker.timer.tick(time.delta());
-                if blinker.timer.just_finished() {
-                    if blinker.number_of_blinks <= 0 {
-                        commands.entity(entity).despawn_recursive();
-                        continue;
+    for (entity, mut tweens, mut transform, instances) in &mut tweened {
+        if tweens.tweens.is_empty() {
+            continue;
+        }
+        for tween in &mut tweens.tweens {
+            tween.tick(time.delta());
+            let t = tween.progress();
+            match tween.property {
+                TweenProperty::Scale { from, to } => {
+                    if let Some(transform) = transform.as_mut() {
+                        transform.scale = from.lerp(to, t);
                     }
-
-                    if blinker.duration < 0. {
-                        blinker.duration = 0.;
-                        blinker.direction = 1.;
-                        blinker.number_of_blinks -= 1;
+                }
+                property => {
+                    let Some(instances) = instances else {
+                        continue;
+                    };
+                    let originals = instances.materials.iter().zip(&instances.emissive);
+                    for (handle, &emissive) in originals {
+                        let Some(material) = materials.get_mut(handle) else {
+                            continue;
+                        };
+                        match property {
+                            TweenProperty::Alpha { from, to } => {
+                                material.base_color.set_a(from + (to - from) * t);
+                            }
+                            TweenProperty::Emissive { from, to } => {
+                                material.emissive = lerp_color(from, to, t);
+                            }
+                            TweenProperty::Glow { to } => {
+                                material.emissive = lerp_color(emissive, to, t);
+                            }
+                            TweenProperty::Color { from, to } => {
+                                material.base_color = lerp_color(from, to, t);
+                            }
+                            TweenProperty::Scale { .. } => {}
+                        }
                     }
+                }
+            }
+        }
 
-                    if blinker.duration > blinker.duration_const {
-                        blinker.duration = blinker.duration_const;
-                        blinker.direction = -1.;
-                    }
+        tweens.tweens.retain(|tween| {
+            if tween.finished() {
+                completed.send(TweenCompleted {
+                    entity,
+                    label: tween.label.clone(),
+                });
+            }
+            !tween.finished()
+        });
+    }
+}
 
-                    blinker.duration += (blinker.speed + time.delta_seconds()) * blinker.direction;
+fn add_tween(commands: &mut Commands, entity: Entity, tweens: Option<Mut<Tweens>>, tween: Tween) {
+    match tweens {
+        Some(mut tweens) => tweens.add(tween),
+        None => {
+            commands.entity(entity).insert(Tweens::new(tween));
+        }
+    }
+}
 
-                    let alpha =
-                        map_value_to_range(blinker.duration, 0., blinker.duration_const, 0., 1.);
+/// Flashes units that take damage.
+pub fn hit_flash_system(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut units: Query<Option<&mut Tweens>>,
+) {
+    for damage in damage_events.iter() {
+        let Ok(tweens) = units.get_mut(damage.target) else {
+            continue;
+        };
+        let flash = Tween::new(
+            "hit",
+            TweenProperty::Glow {
+                to: HIT_FLASH_COLOR,
+            },
+            HIT_FLASH_DURATION / 2.,
+        )
+        .with_easing(Easing::QuadOut)
+        .ping_pong();
+        add_tween(&mut commands, damage.target, tweens, flash);
+    }
+}
 
-                    material.base_color.set_a(alpha);
-                    material.emissive.set_a(alpha);
-                }
-            }
-        }
+/// Pulses units as they are selected.
+pub fn selection_flash_system(
+    mut commands: Commands,
+    mut selected: Query<(Entity, Option<&mut Tweens>), Added<Selected>>,
+) {
+    for (unit, tweens) in &mut selected {
+        let pulse = Tween::new(
+            "selected",
+            TweenProperty::Glow {
+                to: SELECTION_FLASH_COLOR,
+            },
+            SELECTION_FLASH_DURATION / 2.,
+        )
+        .with_easing(Easing::SineInOut)
+        .ping_pong();
+        add_tween(&mut commands, unit, tweens, pulse);
     }
 }
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        mechanics::{Destination, Rotating, Team, Unit, Weapon},
+        orders::Order,
+    },
+    constants::units::{GUARD_FOLLOW_SLACK, GUARD_LEASH},
+    systems::{combat::DamageEvent, commands::approach},
+    util::ground_distance,
+};
+
+/// Keeps guards next to their ward. Armed guards engage whichever enemy last
+/// damaged the ward or themselves, and give up the chase once it would take
+/// them beyond the leash. Guards whose ward is gone go idle.
+pub fn guard_system(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut guards: Query<(
+        Entity,
+        &Transform,
+        &mut Order,
+        Option<&Team>,
+        Option<&Weapon>,
+        Option<&Destination>,
+    )>,
+    units: Query<(&Transform, Option<&Team>), With<Unit>>,
+) {
+    let attacks: Vec<(Entity, Entity)> = damage_events
+        .iter()
+        .filter_map(|damage| damage.source.map(|source| (damage.target, source)))
+        .collect();
+
+    for (guard, transform, mut order, team, weapon, destination) in &mut guards {
+        let Order::Guard {
+            ward,
+            offset,
+            engaging,
+        } = *order
+        else {
+            continue;
+        };
+        let Ok((ward_transform, _)) = units.get(ward) else {
+            *order = Order::Idle;
+            commands
+                .entity(guard)
+                .remove::<Destination>()
+                .remove::<Rotating>();
+            continue;
+        };
+        let team = team.copied().unwrap_or_default();
+        let position = transform.translation;
+        let ward_position = ward_transform.translation;
+
+        let mut now_engaging = engaging;
+        if weapon.is_some() && now_engaging.is_none() {
+            now_engaging = attacks
+                .iter()
+                .rev()
+                .filter(|(target, _)| *target == ward || *target == guard)
+                .map(|(_, source)| *source)
+                .find(|source| {
+                    units.get(*source).map_or(false, |(_, source_team)| {
+                        source_team.copied().unwrap_or_default() != team
+                    })
+                });
+        }
+        if now_engaging.is_some() && ground_distance(position, ward_position) > GUARD_LEASH {
+            now_engaging = None;
+        }
+        if now_engaging != engaging {
+            *order = Order::Guard {
+                ward,
+                offset,
+                engaging: now_engaging,
+            };
+        }
+
+        if now_engaging.is_none() {
+            let post = ward_position + offset;
+            if ground_distance(position, post) > GUARD_FOLLOW_SLACK {
+                approach(&mut commands, guard, destination, post);
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        input::{
+            is_modifier_key, ActionState, Binding, BindingContext, InputBindings, InputKind,
+            Modifiers, PendingRebind, RebindRequest,
+        },
+        mechanics::Selected,
+    },
+    constants::input::{BINDINGS_FILE, USER_BINDINGS_FILE},
+};
+
+pub fn update_action_state(
+    bindings: Res<InputBindings>,
+    pending_rebind: Res<PendingRebind>,
+    keyboard: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    selected: Query<(), With<Selected>>,
+    mut actions: ResMut<ActionState>,
+) {
+    // Swallow input while waiting for a new binding.
+    if pending_rebind.0.is_some() {
+        actions.update(HashSet::new());
+        return;
+    }
+
+    let modifiers = Modifiers::held(&keyboard);
+    let units_selected = !selected.is_empty();
+    let is_down = |input: InputKind| match input {
+        InputKind::Key(key) => keyboard.pressed(key),
+        InputKind::Mouse(button) => mouse.pressed(button),
+        InputKind::Gamepad(button_type) => gamepads
+            .iter()
+            .any(|gamepad| gamepad_buttons.pressed(GamepadButton::new(gamepad, button_type))),
+    };
+
+    let pressed = bindings
+        .bindings
+        .iter()
+        .filter(|(_, bindings)| {
+            bindings.iter().any(|binding| {
+                binding.modifiers == modifiers
+                    && binding.context.applies(units_selected)
+                    && is_down(binding.input)
+            })
+        })
+        .map(|(action, _)| *action)
+        .collect();
+
+    actions.update(pressed);
+}
+
+pub fn rebind_system(
+    mut requests: EventReader<RebindRequest>,
+    mut pending_rebind: ResMut<PendingRebind>,
+    mut bindings: ResMut<InputBindings>,
+    keyboard: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+) {
+    if let Some(request) = requests.iter().last() {
+        pending_rebind.0 = Some(request.action);
+        return;
+    }
+
+    let Some(action) = pending_rebind.0 else {
+        return;
+    };
+
+    let input = keyboard
+        .get_just_pressed()
+        .find(|key| !is_modifier_key(**key))
+        .map(|key| InputKind::Key(*key))
+        .or_else(|| {
+            mouse
+                .get_just_pressed()
+                .next()
+                .map(|b| InputKind::Mouse(*b))
+        })
+        .or_else(|| {
+            gamepad_buttons
+                .get_just_pressed()
+                .next()
+                .map(|b| InputKind::Gamepad(b.button_type))
+        });
+
+    if let Some(input) = input {
+        // A rebound action keeps the context it had.
+        let context = bindings
+            .bindings
+            .get(&action)
+            .and_then(|bindings| bindings.first())
+            .map_or(BindingContext::Always, |binding| binding.context);
+        let binding = Binding::with(input, Modifiers::held(&keyboard)).when(context);
+        info!("Bound {:?} to {:?}", action, binding);
+        bindings.rebind(action, binding);
+        // The shipped file stays as it is; only what differs from it is saved.
+        let defaults = InputBindings::load_or_default(BINDINGS_FILE);
+        bindings.overrides(&defaults).save(USER_BINDINGS_FILE);
+        pending_rebind.0 = None;
+    }
+}
@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+
+use crate::components::mechanics::Lifetime;
+
+pub fn lifetime_despawn_system(
+    mut commands: Commands,
+    mut entities: Query<(Entity, &mut Lifetime)>,
+    time: Res<Time>,
+) {
+    for (entity, mut lifetime) in &mut entities {
+        lifetime.timer.tick(time.delta());
+        if lifetime.timer.just_finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    components::vision::Obstacle,
+    constants::{constants::GROUND_LEVEL, economy::BUILDING_HEIGHT, vision::*},
+    util::ground_distance,
+    Game,
+};
+
+/// Sight line checks against the board's terrain heights, finished buildings
+/// and `Obstacle`s, shared by the fog of war and weapons.
+#[derive(SystemParam)]
+pub struct LineOfSight<'w, 's> {
+    game: Res<'w, Game>,
+    obstacles: Query<'w, 's, (&'static Transform, &'static Obstacle)>,
+}
+
+impl<'w, 's> LineOfSight<'w, 's> {
+    pub fn terrain_height(&self, position: Vec3) -> f32 {
+        terrain_height(&self.game, position)
+    }
+
+    /// `base` plus a bonus for standing on high ground.
+    pub fn sight_range(&self, base: f32, position: Vec3) -> f32 {
+        let elevation = self.terrain_height(position) - GROUND_LEVEL;
+        base + elevation.max(0.) * HIGH_GROUND_SIGHT_BONUS
+    }
+
+    /// Where every `Obstacle` stands. Gather them once per system run and
+    /// check each sight line against them with `can_see_past`.
+    pub fn obstacles(&self) -> Vec<(Vec3, Obstacle)> {
+        self.obstacles
+            .iter()
+            .map(|(transform, obstacle)| (transform.translation, *obstacle))
+            .collect()
+    }
+
+    /// Whether a unit at `from` can see whatever stands at `to`, given the
+    /// `obstacles`.
+    pub fn can_see_past(&self, obstacles: &[(Vec3, Obstacle)], from: Vec3, to: Vec3) -> bool {
+        is_line_clear(&self.game, obstacles, from, to)
+    }
+
+    /// Which board cells a finished building stands on, row by row.
+    pub fn blocked_cells(&self) -> Vec<bool> {
+        self.game
+            .board
+            .iter()
+            .flatten()
+            .map(|cell| cell.blocked)
+            .collect()
+    }
+}
+
+fn terrain_height(game: &Game, position: Vec3) -> f32 {
+    let height = game
+        .cell_index(position)
+        .and_then(|index| game.cell(index))
+        .map_or(0., |cell| cell.height);
+    GROUND_LEVEL + height
+}
+
+/// Walks the sight line between eye height over `from` and `to`, failing if
+/// it dips under the terrain, a finished building or an obstacle on the way.
+/// The board cells at either end never block, so units can see out of and
+/// into the cell they stand in.
+pub fn is_line_clear(game: &Game, obstacles: &[(Vec3, Obstacle)], from: Vec3, to: Vec3) -> bool {
+    let start = Vec3::new(from.x, terrain_height(game, from) + EYE_HEIGHT, from.z);
+    let end = Vec3::new(to.x, terrain_height(game, to) + EYE_HEIGHT, to.z);
+    let distance = ground_distance(from, to);
+    let steps = (distance / LINE_OF_SIGHT_STEP).ceil() as usize;
+    let ends = [game.cell_index(from), game.cell_index(to)];
+    // Only obstacles near the line can block it, and never the ends themselves.
+    let near_line = |(position, obstacle): &&(Vec3, Obstacle)| {
+        ground_distance(*position, from) > obstacle.radius
+            && ground_distance(*position, to) > obstacle.radius
+            && distance_to_segment(*position, from, to) <= obstacle.radius
+    };
+
+    (1..steps).all(|step| {
+        let point = start.lerp(end, step as f32 / steps as f32);
+        let index = game.cell_index(point);
+        let under_terrain = !ends.contains(&index)
+            && index
+                .and_then(|index| game.cell(index))
+                .map_or(false, |cell| {
+                    let top = GROUND_LEVEL
+                        + cell.height
+                        + if cell.blocked { BUILDING_HEIGHT } else { 0. };
+                    point.y < top
+                });
+        let behind_obstacle = obstacles
+            .iter()
+            .filter(near_line)
+            .any(|(position, obstacle)| {
+                ground_distance(point, *position) <= obstacle.radius
+                    && point.y < terrain_height(game, *position) + obstacle.height
+            });
+        !under_terrain && !behind_obstacle
+    })
+}
+
+/// Ground distance from `point` to the segment between `a` and `b`.
+fn distance_to_segment(point: Vec3, a: Vec3, b: Vec3) -> f32 {
+    let flat = |v: Vec3| Vec2::new(v.x, v.z);
+    let (point, a, b) = (flat(point), flat(a), flat(b));
+    let along = b - a;
+    let t = if along.length_squared() > 0. {
+        ((point - a).dot(along) / along.length_squared()).clamp(0., 1.)
+    } else {
+        0.
+    };
+    point.distance(a + along * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::is_line_clear;
+    use crate::{
+        components::vision::Obstacle,
+        constants::{constants::GROUND_LEVEL, plane::CELL_SIZE},
+        systems::spawn_plane::Cell,
+        Game,
+    };
+
+    fn board() -> Game {
+        Game {
+            board: vec![vec![Cell::default(); 3]; 3],
+            ..default()
+        }
+    }
+
+    fn cell_center(i: usize, j: usize) -> Vec3 {
+        Vec3::new(
+            (i as f32 + 0.5) * CELL_SIZE,
+            GROUND_LEVEL,
+            (j as f32 + 0.5) * CELL_SIZE,
+        )
+    }
+
+    #[test]
+    fn flat_open_ground_does_not_block() {
+        assert!(is_line_clear(
+            &board(),
+            &[],
+            cell_center(0, 0),
+            cell_center(2, 0)
+        ));
+    }
+
+    #[test]
+    fn a_ridge_between_two_valleys_blocks_but_its_top_sees_both() {
+        let mut game = board();
+        game.board[0][1].height = 3.;
+
+        assert!(!is_line_clear(
+            &game,
+            &[],
+            cell_center(0, 0),
+            cell_center(2, 0)
+        ));
+        assert!(is_line_clear(
+            &game,
+            &[],
+            cell_center(1, 0),
+            cell_center(2, 0)
+        ));
+    }
+
+    #[test]
+    fn obstacles_block_only_lines_through_them() {
+        let obstacle = Obstacle {
+            radius: 1.,
+            height: 5.,
+        };
+        let from = Vec3::new(2., GROUND_LEVEL, 2.);
+        let wall = Vec3::new(6., GROUND_LEVEL, 2.);
+
+        assert!(!is_line_clear(
+            &board(),
+            &[(wall, obstacle)],
+            from,
+            Vec3::new(10., GROUND_LEVEL, 2.)
+        ));
+        assert!(is_line_clear(
+            &board(),
+            &[(wall, obstacle)],
+            from,
+            Vec3::new(2., GROUND_LEVEL, 10.)
+        ));
+    }
+}
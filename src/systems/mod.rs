@@ -1,7 +1,25 @@
+pub mod abilities;
+pub mod avoidance;
+pub mod buildings;
+pub mod combat;
+pub mod commands;
+pub mod definitions;
+pub mod economy;
 pub mod effects;
+pub mod guard;
+pub mod input;
+pub mod lifetime;
+pub mod line_of_sight;
 pub mod mouse;
 pub mod movement;
+pub mod production;
+pub mod research;
 pub mod rotation;
+pub mod selection;
 pub mod spawn_plane;
 pub mod spawn_unit;
+pub mod stats;
+pub mod stuck;
+pub mod transport;
 pub mod update_lights;
+pub mod vision;
@@ -1,21 +1,43 @@
 use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
 
 use crate::{
-    components::mechanics::{Destination, MovementSpeed},
+    components::{
+        mechanics::{Destination, KinematicVelocity, MovementSpeed, PhysicsMovement},
+        orders::Order,
+        stats::{effective, Stat, StatEffects},
+        transport::Loaded,
+    },
     constants::{
         constants::GROUND_LEVEL,
-        plane::GAME_BOUNDS,
-        units::{ARRIVAL_TOLERANCE, SOCIAL_DISTANCE},
+        plane::{BLOCKED_CELL_RADIUS, GAME_BOUNDS},
+        units::{
+            ARRIVAL_TOLERANCE, AVOIDANCE_NEIGHBOUR_DISTANCE, AVOIDANCE_RADIUS,
+            AVOIDANCE_TIME_HORIZON, PHYSICS_LINEAR_DAMPING, SOCIAL_DISTANCE, UNIT_COLLIDER_RADIUS,
+        },
     },
+    systems::avoidance::{avoid_neighbours, Agent, StaticObstacle},
     util::{are_positions_near, keep_in_bounds},
     Game,
 };
 
 pub fn adjust_still_units_system(
     mut units: Query<
-        (Entity, &mut Transform, &MovementSpeed),
-        (With<MovementSpeed>, Without<Destination>),
+        (
+            Entity,
+            &mut Transform,
+            &MovementSpeed,
+            Option<&Order>,
+            Option<&StatEffects>,
+        ),
+        (
+            With<MovementSpeed>,
+            Without<Destination>,
+            Without<PhysicsMovement>,
+            Without<Loaded>,
+        ),
     >,
+    physics_units: Query<(Entity, &Transform), (With<PhysicsMovement>, Without<Loaded>)>,
     mut game: ResMut<Game>,
     time: Res<Time>,
 ) {
@@ -23,9 +45,14 @@ pub fn adjust_still_units_system(
         let all_units_positions: Vec<(Entity, Vec3)> = units
             .into_iter()
             .map(|t| return (t.0, t.1.translation))
+            .chain(physics_units.iter().map(|t| (t.0, t.1.translation)))
             .collect();
 
-        for (entity, mut transform, speed) in &mut units {
+        for (entity, mut transform, speed, order, effects) in &mut units {
+            // Units holding position stand their ground; the rest make room.
+            if order == Some(&Order::Hold) {
+                continue;
+            }
             if game.mechanics.move_cooldown.tick(time.delta()).finished() {
                 let new_destination = adjust_movement_for_neighbors(
                     &entity,
@@ -34,44 +61,131 @@ pub fn adjust_still_units_system(
                     &all_units_positions,
                 );
 
-                transform.translation = move_unit(
-                    &transform.translation,
-                    new_destination,
-                    &speed,
-                    time.delta_seconds(),
-                )
+                let speed = effective(effects, Stat::Speed, speed.value);
+                let velocity = preferred_velocity(transform.translation, new_destination, speed);
+                transform.translation =
+                    move_unit(&transform.translation, velocity, time.delta_seconds())
             }
         }
     }
 }
 
+/// The cells of finished buildings near `position`, apart from those of the
+/// building `destination` is on so that units can still walk up to a depot or
+/// garrison they were sent to.
+fn blocked_cells(game: &Game, position: Vec3, destination: Vec3) -> Vec<StaticObstacle> {
+    let position = Vec2::new(position.x, position.z);
+    let target = game
+        .cell_index(destination)
+        .and_then(|index| game.cell(index))
+        .and_then(|cell| cell.occupant);
+    game.board
+        .iter()
+        .enumerate()
+        .flat_map(|(j, row)| row.iter().enumerate().map(move |(i, cell)| ((i, j), cell)))
+        .filter(|(_, cell)| cell.blocked && (target.is_none() || cell.occupant != target))
+        .map(|((i, j), _)| {
+            let center = Game::footprint_center((i, j), (1, 1));
+            StaticObstacle::new(center, BLOCKED_CELL_RADIUS)
+        })
+        .filter(|obstacle| {
+            obstacle.position.distance(position) < AVOIDANCE_NEIGHBOUR_DISTANCE + obstacle.radius
+        })
+        .collect()
+}
+
+/// Moves kinematic units toward their destination. Each unit heads straight
+/// for it, and local avoidance bends that into a velocity that steers clear
+/// of its neighbours, moving or not, and of finished buildings.
 pub fn movement_system(
     mut commands: Commands,
     time: Res<Time>,
     mut game: ResMut<Game>,
     mut units: Query<
-        (Entity, &mut Transform, &mut Destination, &MovementSpeed),
-        (With<Destination>, With<MovementSpeed>),
+        (
+            Entity,
+            &mut Transform,
+            &Destination,
+            &MovementSpeed,
+            Option<&mut KinematicVelocity>,
+            Option<&StatEffects>,
+        ),
+        (With<Destination>, Without<PhysicsMovement>),
+    >,
+    still_units: Query<
+        (Entity, &Transform),
+        (
+            With<MovementSpeed>,
+            Without<Destination>,
+            Without<PhysicsMovement>,
+            Without<Loaded>,
+        ),
     >,
+    physics_units: Query<(Entity, &Transform, &Velocity), (With<PhysicsMovement>, Without<Loaded>)>,
 ) {
-    let units_positions: Vec<(Entity, Vec3)> = units
-        .into_iter()
-        .map(|t| return (t.0, t.1.translation))
+    let delta_seconds = time.delta_seconds();
+    let agents: Vec<(Entity, Agent)> = units
+        .iter()
+        .map(
+            |(entity, transform, destination, speed, velocity, effects)| {
+                let position = transform.translation;
+                let speed = effective(effects, Stat::Speed, speed.value);
+                let velocity = velocity.map_or_else(
+                    || preferred_velocity(position, destination.0, speed),
+                    |velocity| velocity.0,
+                );
+                (entity, Agent::new(position, velocity, AVOIDANCE_RADIUS))
+            },
+        )
+        .chain(still_units.iter().map(|(entity, transform)| {
+            (
+                entity,
+                Agent::new(transform.translation, Vec3::ZERO, AVOIDANCE_RADIUS),
+            )
+        }))
+        .chain(physics_units.iter().map(|(entity, transform, velocity)| {
+            (
+                entity,
+                Agent::new(transform.translation, velocity.linvel, UNIT_COLLIDER_RADIUS),
+            )
+        }))
         .collect();
-    for (entity, mut transform, destination, speed) in &mut units {
+
+    for (entity, mut transform, destination, speed, velocity, effects) in &mut units {
         if game.mechanics.move_cooldown.tick(time.delta()).finished() {
-            let new_destination = adjust_movement_for_neighbors(
-                &entity,
-                &transform.translation,
-                destination.0,
-                &units_positions,
+            let position = transform.translation;
+            let speed = effective(effects, Stat::Speed, speed.value);
+            let preferred = preferred_velocity(position, destination.0, speed);
+            let agent = Agent::new(
+                position,
+                velocity.as_ref().map_or(preferred, |velocity| velocity.0),
+                AVOIDANCE_RADIUS,
             );
-            transform.translation = move_unit(
-                &transform.translation,
-                new_destination,
-                &speed,
-                time.delta_seconds(),
+            let neighbours = agents
+                .iter()
+                .filter(|(other, other_agent)| {
+                    *other != entity
+                        && other_agent.position.distance(agent.position)
+                            < AVOIDANCE_NEIGHBOUR_DISTANCE
+                })
+                .map(|(_, other_agent)| *other_agent);
+            let avoiding = avoid_neighbours(
+                &agent,
+                preferred,
+                preferred.length(),
+                neighbours,
+                &blocked_cells(&game, position, destination.0),
+                AVOIDANCE_TIME_HORIZON,
+                delta_seconds,
             );
+
+            transform.translation = move_unit(&position, avoiding, delta_seconds);
+            match velocity {
+                Some(mut velocity) => velocity.0 = avoiding,
+                None => {
+                    commands.entity(entity).insert(KinematicVelocity(avoiding));
+                }
+            }
             stop_at_destination(
                 &mut commands,
                 entity,
@@ -83,6 +197,83 @@ pub fn movement_system(
     }
 }
 
+/// The Rapier body, collider and damping that put a unit under physics
+/// movement. Its collider keeps the same size whatever the model's scale.
+pub fn physics_body() -> impl Bundle {
+    (
+        PhysicsMovement,
+        RigidBody::Dynamic,
+        Velocity::default(),
+        ExternalImpulse::default(),
+        LockedAxes::TRANSLATION_LOCKED_Y | LockedAxes::ROTATION_LOCKED,
+        Collider::ball(UNIT_COLLIDER_RADIUS),
+        ColliderScale::Absolute(Vec3::ONE),
+        Restitution::coefficient(0.01),
+        Damping {
+            linear_damping: PHYSICS_LINEAR_DAMPING,
+            angular_damping: 1.0,
+        },
+    )
+}
+
+/// Steers physics units toward their destination at the pace kinematic units
+/// move at, around finished buildings, and leaves collisions and pushing to
+/// Rapier. Units without a destination are left to coast to a stop.
+pub fn steer_physics_units_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    game: Res<Game>,
+    mut units: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Velocity,
+            &MovementSpeed,
+            &Destination,
+            Option<&StatEffects>,
+        ),
+        With<PhysicsMovement>,
+    >,
+) {
+    let delta_seconds = time.delta_seconds();
+    for (entity, transform, mut velocity, speed, destination, effects) in &mut units {
+        let position = transform.translation;
+        if are_positions_near(&destination.0, &position, ARRIVAL_TOLERANCE) {
+            velocity.linvel = Vec3::ZERO;
+            commands.entity(entity).remove::<Destination>();
+            continue;
+        }
+        let speed = effective(effects, Stat::Speed, speed.value);
+        let desired = desired_velocity(position, destination.0, speed, delta_seconds);
+        let agent = Agent::new(position, velocity.linvel, UNIT_COLLIDER_RADIUS);
+        velocity.linvel = avoid_neighbours(
+            &agent,
+            desired,
+            desired.length(),
+            std::iter::empty(),
+            &blocked_cells(&game, position, destination.0),
+            AVOIDANCE_TIME_HORIZON,
+            delta_seconds,
+        );
+    }
+}
+
+/// The velocity that covers the same ground in `delta_seconds` as `move_unit`,
+/// stopping short of the edge of the map.
+pub fn desired_velocity(
+    unit_position: Vec3,
+    destination: Vec3,
+    unit_speed: f32,
+    delta_seconds: f32,
+) -> Vec3 {
+    if delta_seconds <= 0. {
+        return Vec3::ZERO;
+    }
+    let step = preferred_velocity(unit_position, destination, unit_speed) * delta_seconds;
+    let next_position = keep_in_bounds(GAME_BOUNDS, unit_position + step, 2.);
+    (next_position - unit_position) * Vec3::new(1., 0., 1.) / delta_seconds
+}
+
 fn stop_at_destination(
     commands: &mut Commands,
     unit: Entity,
@@ -95,14 +286,16 @@ fn stop_at_destination(
     }
 }
 
-fn move_unit(
-    unit_position: &Vec3,
-    new_destination: Vec3,
-    unit_speed: &MovementSpeed,
-    delta_seconds: f32,
-) -> Vec3 {
-    let mut new_unit_position =
-        unit_position.lerp(new_destination, unit_speed.value * delta_seconds);
+/// How a unit would like to head for `target` with nothing in the way: a share
+/// of the remaining distance per second, so it slows down as it arrives.
+pub fn preferred_velocity(unit_position: Vec3, target: Vec3, unit_speed: f32) -> Vec3 {
+    let mut velocity = (target - unit_position) * unit_speed;
+    velocity.y = 0.;
+    velocity
+}
+
+fn move_unit(unit_position: &Vec3, velocity: Vec3, delta_seconds: f32) -> Vec3 {
+    let mut new_unit_position = *unit_position + velocity * delta_seconds;
     new_unit_position = keep_in_bounds(GAME_BOUNDS, new_unit_position, 2.);
     new_unit_position.y = GROUND_LEVEL;
     new_unit_position
@@ -131,3 +324,24 @@ fn adjust_movement_for_neighbors(
     }
     new_destination
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{desired_velocity, move_unit, preferred_velocity};
+    use crate::constants::constants::GROUND_LEVEL;
+
+    #[test]
+    fn desired_velocity_matches_a_kinematic_step() {
+        let speed = 2.;
+        let from = Vec3::new(5., GROUND_LEVEL, 5.);
+        let to = Vec3::new(15., GROUND_LEVEL, 8.);
+        let delta = 1. / 60.;
+
+        let kinematic = move_unit(&from, preferred_velocity(from, to, speed), delta);
+        let physics = from + desired_velocity(from, to, speed, delta) * delta;
+
+        assert!(kinematic.distance(physics) < 0.001);
+    }
+}
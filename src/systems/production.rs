@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        buildings::{Building, BuildingDefinitions, ProductionQueue, QueuedUnit, RallyPoint},
+        economy::Stockpiles,
+        mechanics::{Destination, Rotating, Team},
+        orders::Order,
+    },
+    constants::{
+        economy::PRODUCTION_QUEUE_LIMIT, plane::BLOCKED_CELL_RADIUS, units::AVOIDANCE_RADIUS,
+    },
+    systems::{
+        commands::{CommandEvent, UnitCommand},
+        spawn_unit::spawn_unit_at,
+    },
+    util::ground_distance,
+};
+
+/// Queues, cancels and rallies for production buildings. With several
+/// buildings selected, a new unit goes to the shortest queue and a cancel
+/// comes off the longest, so a group of buildings acts like one.
+pub fn production_commands_system(
+    mut commands: Commands,
+    mut events: EventReader<CommandEvent>,
+    definitions: Res<BuildingDefinitions>,
+    mut stockpiles: ResMut<Stockpiles>,
+    mut producers: Query<(Entity, &Building, Option<&Team>, &mut ProductionQueue)>,
+) {
+    for event in events.iter() {
+        match &event.command {
+            UnitCommand::Produce(unit) => {
+                let shortest = producers
+                    .iter_many(&event.units)
+                    .filter(|(_, building, ..)| {
+                        definitions.production(&building.kind, unit).is_some()
+                    })
+                    .filter(|(.., queue)| queue.items.len() < PRODUCTION_QUEUE_LIMIT)
+                    .min_by_key(|(.., queue)| queue.items.len())
+                    .map(|(producer, ..)| producer);
+                let Some(producer) = shortest else {
+                    continue;
+                };
+                let Ok((_, building, team, mut queue)) = producers.get_mut(producer) else {
+                    continue;
+                };
+                let Some(option) = definitions.production(&building.kind, unit) else {
+                    continue;
+                };
+                if !stockpiles.try_spend(team.copied().unwrap_or_default(), option.cost) {
+                    info!("Not enough resources for {}", unit);
+                    continue;
+                }
+                queue.items.push_back(QueuedUnit {
+                    unit: unit.clone(),
+                    cost: option.cost,
+                    timer: Timer::from_seconds(option.build_time, TimerMode::Once),
+                });
+            }
+            UnitCommand::CancelProduction => {
+                let longest = producers
+                    .iter_many(&event.units)
+                    .filter(|(.., queue)| !queue.items.is_empty())
+                    .max_by_key(|(.., queue)| queue.items.len())
+                    .map(|(producer, ..)| producer);
+                let Some(producer) = longest else {
+                    continue;
+                };
+                let Ok((_, _, team, mut queue)) = producers.get_mut(producer) else {
+                    continue;
+                };
+                if let Some(cancelled) = queue.items.pop_back() {
+                    stockpiles.add(team.copied().unwrap_or_default(), cancelled.cost);
+                }
+            }
+            UnitCommand::Move(point) => {
+                for (producer, ..) in producers.iter_many(&event.units) {
+                    commands.entity(producer).insert(RallyPoint(*point));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Runs the front of every queue. Finished units come out on the side of the
+/// building facing its rally point and head there.
+pub fn production_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut producers: Query<(
+        &Transform,
+        Option<&Team>,
+        Option<&RallyPoint>,
+        &mut ProductionQueue,
+    )>,
+) {
+    for (transform, team, rally, mut queue) in &mut producers {
+        let Some(front) = queue.items.front_mut() else {
+            continue;
+        };
+        if !front.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        let Some(finished) = queue.items.pop_front() else {
+            continue;
+        };
+
+        let position = transform.translation;
+        let towards = rally
+            .filter(|RallyPoint(point)| ground_distance(*point, position) > f32::EPSILON)
+            .map_or(Vec3::Z, |RallyPoint(point)| {
+                Vec3::new(point.x - position.x, 0., point.z - position.z).normalize()
+            });
+        let unit = spawn_unit_at(
+            &mut commands,
+            &finished.unit,
+            team.copied().unwrap_or_default(),
+            // Clear of the building, which units steer around.
+            position + towards * (BLOCKED_CELL_RADIUS + AVOIDANCE_RADIUS),
+        );
+        if let Some(RallyPoint(point)) = rally {
+            commands
+                .entity(unit)
+                .insert((Destination(*point), Rotating, Order::Move(*point)));
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        buildings::{Building, UnderConstruction},
+        economy::Stockpiles,
+        mechanics::{Health, MovementSpeed, RotationSpeed, Team, UnitType, Weapon},
+        research::{ResearchDefinitions, Researching, TeamResearch},
+        stats::{ModifierStack, Stat},
+        vision::SightRange,
+    },
+    constants::vision::DEFAULT_SIGHT_RANGE,
+    systems::commands::{CommandEvent, UnitCommand},
+    units::definitions::{UnitDefinition, UnitDefinitions},
+};
+
+/// A unit type's stats with a team's modifiers on top.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitStats {
+    pub health: f32,
+    pub speed: f32,
+    pub rotation_speed: f32,
+    pub damage: Option<f32>,
+    pub range: Option<f32>,
+    pub sight_range: f32,
+}
+
+impl UnitStats {
+    pub fn new(definition: &UnitDefinition, unit_type: &str, modifiers: &ModifierStack) -> Self {
+        let apply = |stat, base| modifiers.apply(stat, unit_type, base);
+        Self {
+            health: apply(Stat::Health, definition.health),
+            speed: apply(Stat::Speed, definition.speed),
+            rotation_speed: apply(Stat::RotationSpeed, definition.rotation_speed),
+            damage: definition
+                .weapon
+                .as_ref()
+                .map(|weapon| apply(Stat::Damage, weapon.damage)),
+            range: definition
+                .weapon
+                .as_ref()
+                .map(|weapon| apply(Stat::Range, weapon.range)),
+            sight_range: apply(
+                Stat::SightRange,
+                definition.sight_range.unwrap_or(DEFAULT_SIGHT_RANGE),
+            ),
+        }
+    }
+}
+
+/// Starts research at the first selected building that can take it on. Each
+/// item is researched once per team, one at a time per building, and only
+/// after what it requires.
+pub fn research_commands_system(
+    mut commands: Commands,
+    mut events: EventReader<CommandEvent>,
+    definitions: Res<ResearchDefinitions>,
+    research: Res<TeamResearch>,
+    mut stockpiles: ResMut<Stockpiles>,
+    buildings: Query<
+        (Entity, &Building, Option<&Team>, Option<&Researching>),
+        Without<UnderConstruction>,
+    >,
+) {
+    for event in events.iter() {
+        let UnitCommand::Research(name) = &event.command else {
+            continue;
+        };
+        let Some(definition) = definitions.get(name) else {
+            continue;
+        };
+        let idle = buildings
+            .iter_many(&event.units)
+            .filter(|(_, building, ..)| building.kind == definition.building)
+            .find(|(.., researching)| researching.is_none());
+        let Some((building, _, team, _)) = idle else {
+            continue;
+        };
+        let team = team.copied().unwrap_or_default();
+        let underway = buildings.iter().any(|(_, _, other_team, researching)| {
+            other_team.copied().unwrap_or_default() == team
+                && researching.map_or(false, |researching| researching.research == *name)
+        });
+        if underway || research.is_complete(team, name) || !research.can_start(team, definition) {
+            continue;
+        }
+        if !stockpiles.try_spend(team, definition.cost) {
+            info!("Not enough resources for {}", name);
+            continue;
+        }
+        commands.entity(building).insert(Researching {
+            research: name.clone(),
+            timer: Timer::from_seconds(definition.research_time, TimerMode::Once),
+        });
+    }
+}
+
+/// Finishes research whose timer is up, adding its modifiers to the team's
+/// stack.
+pub fn research_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    definitions: Res<ResearchDefinitions>,
+    mut research: ResMut<TeamResearch>,
+    mut buildings: Query<(Entity, Option<&Team>, &mut Researching)>,
+) {
+    for (building, team, mut researching) in &mut buildings {
+        if !researching.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        commands.entity(building).remove::<Researching>();
+        if let Some(definition) = definitions.get(&researching.research) {
+            research.complete(
+                team.copied().unwrap_or_default(),
+                &researching.research,
+                definition,
+            );
+        }
+    }
+}
+
+/// Brings the stats of existing units up to date once their team finishes
+/// research. Units keep the damage they have taken; new units get the
+/// modifiers from `apply_unit_definitions`.
+pub fn upgrade_units_system(
+    research: Res<TeamResearch>,
+    definitions: Res<UnitDefinitions>,
+    mut units: Query<
+        (
+            &UnitType,
+            Option<&Team>,
+            &mut Health,
+            &mut MovementSpeed,
+            &mut RotationSpeed,
+            Option<&mut Weapon>,
+            Option<&mut SightRange>,
+        ),
+        Without<Building>,
+    >,
+) {
+    if !research.is_changed() || research.is_added() {
+        return;
+    }
+    for (unit_type, team, mut health, mut speed, mut rotation, weapon, sight) in &mut units {
+        let Some(definition) = definitions.get(&unit_type.0) else {
+            continue;
+        };
+        let modifiers = research.modifiers(team.copied().unwrap_or_default());
+        let stats = UnitStats::new(definition, &unit_type.0, &modifiers);
+
+        if health.max != stats.health {
+            health.value += stats.health - health.max;
+            health.max = stats.health;
+        }
+        speed.value = stats.speed;
+        rotation.value = stats.rotation_speed;
+        if let (Some(mut weapon), Some(damage), Some(range)) = (weapon, stats.damage, stats.range) {
+            weapon.damage = damage;
+            weapon.range = range;
+        }
+        if let Some(mut sight) = sight {
+            sight.value = stats.sight_range;
+        }
+    }
+}
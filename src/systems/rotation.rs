@@ -1,5 +1,8 @@
 use crate::{
-    components::mechanics::{Destination, Rotating, RotationSpeed},
+    components::{
+        mechanics::{Destination, Rotating, RotationSpeed},
+        stats::{effective, Stat, StatEffects},
+    },
     Game,
 };
 use bevy::prelude::*;
@@ -9,12 +12,19 @@ pub fn rotate_system(
     mut commands: Commands,
     mut game: ResMut<Game>,
     mut transforms: Query<
-        (Entity, &mut Transform, &RotationSpeed, &Destination),
+        (
+            Entity,
+            &mut Transform,
+            &RotationSpeed,
+            &Destination,
+            Option<&StatEffects>,
+        ),
         (With<RotationSpeed>, With<Destination>, With<Rotating>),
     >,
 ) {
-    for (entity, mut transform, rotation_speed, destination) in &mut transforms {
+    for (entity, mut transform, rotation_speed, destination, effects) in &mut transforms {
         if game.mechanics.rotate_cooldown.tick(time.delta()).finished() {
+            let rotation_speed = effective(effects, Stat::RotationSpeed, rotation_speed.value);
             let difference = transform.translation - destination.0;
             let mut angle_between_pts = difference.z.atan2(difference.x).to_degrees();
 
@@ -24,8 +34,8 @@ pub fn rotate_system(
 
             let mut new_angle = transform.rotation.to_axis_angle().1.to_degrees();
             match get_heading(angle_between_pts, new_angle) {
-                RotationDirection::CW => new_angle -= rotation_speed.value * time.delta_seconds(),
-                RotationDirection::CCW => new_angle += rotation_speed.value * time.delta_seconds(),
+                RotationDirection::CW => new_angle -= rotation_speed * time.delta_seconds(),
+                RotationDirection::CCW => new_angle += rotation_speed * time.delta_seconds(),
             }
 
             if new_angle > 360.0 {
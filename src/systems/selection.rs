@@ -0,0 +1,351 @@
+use bevy::prelude::*;
+use bevy_iso3d_rts_cursor_plugin::{Cursor, Pickable, Selected as CursorSelected};
+
+use crate::{
+    components::{
+        input::{Action, ActionState},
+        mechanics::{Selected, Team, Unit, UnitType},
+        transport::Loaded,
+        vision::Fogged,
+    },
+    constants::selection::*,
+    plugins::camera::{CameraFocusEvent, RtsCamera},
+};
+
+/// Sent once per frame in which the selection changed, with the new selection.
+pub struct SelectionChanged {
+    pub selected: Vec<Entity>,
+}
+
+/// Saved selections, recalled with the number keys.
+#[derive(Resource, Default, Debug)]
+pub struct ControlGroups {
+    groups: [Vec<Entity>; CONTROL_GROUPS],
+}
+
+impl ControlGroups {
+    pub fn get(&self, group: usize) -> &[Entity] {
+        &self.groups[group]
+    }
+
+    pub fn assign(&mut self, group: usize, units: impl IntoIterator<Item = Entity>) {
+        self.groups[group] = units.into_iter().collect();
+    }
+
+    pub fn add(&mut self, group: usize, units: impl IntoIterator<Item = Entity>) {
+        for unit in units {
+            if !self.groups[group].contains(&unit) {
+                self.groups[group].push(unit);
+            }
+        }
+    }
+
+    /// Drops units that no longer exist, e.g. after they died.
+    pub fn retain(&mut self, mut exists: impl FnMut(Entity) -> bool) {
+        for group in &mut self.groups {
+            group.retain(|unit| exists(*unit));
+        }
+    }
+}
+
+/// Replaces the selection with `units`, keeping the cursor plugin's own
+/// selection in step so its highlights match.
+pub fn select_only(
+    commands: &mut Commands,
+    currently_selected: impl Iterator<Item = Entity>,
+    units: &[Entity],
+) {
+    for unit in currently_selected {
+        if !units.contains(&unit) {
+            commands
+                .entity(unit)
+                .remove::<Selected>()
+                .remove::<CursorSelected>();
+        }
+    }
+    for unit in units {
+        if let Some(mut unit) = commands.get_entity(*unit) {
+            unit.insert(Selected).insert(CursorSelected);
+        }
+    }
+}
+
+/// The cursor plugin owns box and click selection; mirror it into `Selected`.
+pub fn mirror_cursor_selection(
+    mut commands: Commands,
+    added: Query<Entity, Added<CursorSelected>>,
+    removed: RemovedComponents<CursorSelected>,
+) {
+    for unit in &added {
+        commands.entity(unit).insert(Selected);
+    }
+    for unit in removed.iter() {
+        if let Some(mut unit) = commands.get_entity(unit) {
+            unit.remove::<Selected>();
+        }
+    }
+}
+
+/// Units inside a transport or building are not drawn and cannot be picked or
+/// stay selected until they are let out.
+pub fn hide_loaded_units(
+    mut commands: Commands,
+    loaded: Query<Entity, Added<Loaded>>,
+    mut visibilities: Query<&mut Visibility>,
+) {
+    for unit in &loaded {
+        if let Ok(mut visibility) = visibilities.get_mut(unit) {
+            visibility.is_visible = false;
+        }
+        commands
+            .entity(unit)
+            .remove::<Pickable>()
+            .remove::<Selected>()
+            .remove::<CursorSelected>();
+    }
+}
+
+/// Draws unloaded units and lets them be picked again, unless they are out of
+/// the player's sight. Runs after the update stage, where units are unloaded,
+/// since removals are only seen by later stages of the same frame.
+pub fn show_unloaded_units(
+    mut commands: Commands,
+    unloaded: RemovedComponents<Loaded>,
+    mut visibilities: Query<&mut Visibility, (Without<Loaded>, Without<Fogged>)>,
+) {
+    for unit in unloaded.iter() {
+        let Ok(mut visibility) = visibilities.get_mut(unit) else {
+            continue;
+        };
+        visibility.is_visible = true;
+        commands.entity(unit).insert(Pickable);
+    }
+}
+
+pub fn selection_changed_system(
+    added: Query<Entity, Added<Selected>>,
+    removed: RemovedComponents<Selected>,
+    selected: Query<Entity, With<Selected>>,
+    mut selection_changed: EventWriter<SelectionChanged>,
+) {
+    if added.is_empty() && removed.iter().next().is_none() {
+        return;
+    }
+
+    selection_changed.send(SelectionChanged {
+        selected: selected.iter().collect(),
+    });
+}
+
+/// Ctrl+number assigns, Shift+number adds and number recalls. Recalling the
+/// same group twice in quick succession also focuses the camera on it.
+pub fn control_group_system(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    time: Res<Time>,
+    mut groups: ResMut<ControlGroups>,
+    mut last_recall: Local<Option<(usize, f64)>>,
+    selected: Query<Entity, With<Selected>>,
+    units: Query<&Transform, With<Unit>>,
+    entities: Query<Entity>,
+    mut focus_events: EventWriter<CameraFocusEvent>,
+) {
+    // Units inside transports are not `Unit`s for now but stay in their groups.
+    groups.retain(|unit| entities.contains(unit));
+
+    for group in 0..CONTROL_GROUPS as u8 {
+        let index = group as usize;
+
+        if actions.just_pressed(Action::AssignControlGroup(group)) {
+            groups.assign(index, selected.iter());
+        }
+
+        if actions.just_pressed(Action::AddToControlGroup(group)) {
+            groups.add(index, selected.iter());
+        }
+
+        if actions.just_pressed(Action::RecallControlGroup(group)) {
+            // Loaded members stay in the group but are not selected.
+            let members: Vec<Entity> = groups
+                .get(index)
+                .iter()
+                .copied()
+                .filter(|unit| units.contains(*unit))
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            select_only(&mut commands, selected.iter(), &members);
+
+            let now = time.elapsed_seconds_f64();
+            let double_tapped = matches!(*last_recall, Some((last_group, last_time))
+                if last_group == index && now - last_time < CONTROL_GROUP_DOUBLE_TAP);
+            if double_tapped {
+                let positions: Vec<Vec3> = units
+                    .iter_many(&members)
+                    .map(|transform| transform.translation)
+                    .collect();
+                if !positions.is_empty() {
+                    let centre = positions.iter().sum::<Vec3>() / positions.len() as f32;
+                    focus_events.send(CameraFocusEvent(centre));
+                }
+                *last_recall = None;
+            } else {
+                *last_recall = Some((index, now));
+            }
+        }
+    }
+}
+
+/// Double-clicking a unit selects every unit of the same type and team that is
+/// currently on screen.
+pub fn double_click_select_system(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    time: Res<Time>,
+    cursor: Res<Cursor>,
+    mut last_click: Local<Option<f64>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<RtsCamera>>,
+    units: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &UnitType,
+            Option<&Team>,
+            &ComputedVisibility,
+        ),
+        With<Unit>,
+    >,
+    selected: Query<Entity, With<Selected>>,
+) {
+    if !actions.just_pressed(Action::Select) {
+        return;
+    }
+
+    let now = time.elapsed_seconds_f64();
+    let double_clicked = matches!(*last_click, Some(last) if now - last < DOUBLE_CLICK_SECONDS);
+    *last_click = if double_clicked { None } else { Some(now) };
+    if !double_clicked {
+        return;
+    }
+
+    let clicked_at = cursor.location.xyz;
+    let clicked = units
+        .iter()
+        .map(|(_, transform, unit_type, team, _)| {
+            let offset = transform.translation() - clicked_at;
+            (Vec2::new(offset.x, offset.z).length(), unit_type, team)
+        })
+        .filter(|(distance, ..)| *distance < CLICK_SELECT_RADIUS)
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+    let Some((_, clicked_type, clicked_team)) = clicked else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = cameras.get_single() else {
+        return;
+    };
+    let Some(viewport) = camera.logical_viewport_size() else {
+        return;
+    };
+
+    let on_screen = |position: Vec3| {
+        camera
+            .world_to_viewport(camera_transform, position)
+            .map_or(false, |point| {
+                point.cmpge(Vec2::ZERO).all() && point.cmple(viewport).all()
+            })
+    };
+
+    let same_type: Vec<Entity> = units
+        .iter()
+        .filter(|(_, transform, unit_type, team, visibility)| {
+            *unit_type == clicked_type
+                && *team == clicked_team
+                && visibility.is_visible()
+                && on_screen(transform.translation())
+        })
+        .map(|(entity, ..)| entity)
+        .collect();
+
+    select_only(&mut commands, selected.iter(), &same_type);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+    use bevy_iso3d_rts_cursor_plugin::Pickable;
+
+    use super::{hide_loaded_units, show_unloaded_units, ControlGroups};
+    use crate::components::transport::Loaded;
+
+    #[derive(Resource)]
+    struct Unloading(bool);
+
+    /// Lets units out during the update stage, as `unload_units_system` does.
+    fn unload(
+        mut commands: Commands,
+        unloading: Res<Unloading>,
+        loaded: Query<Entity, With<Loaded>>,
+    ) {
+        if unloading.0 {
+            for unit in &loaded {
+                commands.entity(unit).remove::<Loaded>();
+            }
+        }
+    }
+
+    #[test]
+    fn assigning_a_group_replaces_it() {
+        let mut groups = ControlGroups::default();
+        groups.assign(1, [Entity::from_raw(1), Entity::from_raw(2)]);
+
+        groups.assign(1, [Entity::from_raw(3)]);
+
+        assert_eq!(groups.get(1), &[Entity::from_raw(3)]);
+    }
+
+    #[test]
+    fn adding_to_a_group_skips_existing_members() {
+        let mut groups = ControlGroups::default();
+        groups.assign(2, [Entity::from_raw(1)]);
+
+        groups.add(2, [Entity::from_raw(1), Entity::from_raw(2)]);
+
+        assert_eq!(groups.get(2), &[Entity::from_raw(1), Entity::from_raw(2)]);
+    }
+
+    #[test]
+    fn dead_units_are_dropped_from_every_group() {
+        let mut groups = ControlGroups::default();
+        groups.assign(1, [Entity::from_raw(1), Entity::from_raw(2)]);
+        groups.assign(9, [Entity::from_raw(2)]);
+
+        groups.retain(|unit| unit != Entity::from_raw(2));
+
+        assert_eq!(groups.get(1), &[Entity::from_raw(1)]);
+        assert!(groups.get(9).is_empty());
+    }
+
+    #[test]
+    fn unloaded_units_are_drawn_and_pickable_again() {
+        let mut app = App::new();
+        app.insert_resource(Unloading(false))
+            .add_system(unload)
+            .add_system(hide_loaded_units)
+            .add_system_to_stage(CoreStage::PostUpdate, show_unloaded_units);
+        let carrier = app.world.spawn_empty().id();
+        let unit = app
+            .world
+            .spawn((VisibilityBundle::default(), Pickable, Loaded { carrier }))
+            .id();
+
+        app.update();
+        assert!(!app.world.get::<Visibility>(unit).unwrap().is_visible);
+        assert!(app.world.get::<Pickable>(unit).is_none());
+
+        app.world.resource_mut::<Unloading>().0 = true;
+        app.update();
+        assert!(app.world.get::<Visibility>(unit).unwrap().is_visible);
+        assert!(app.world.get::<Pickable>(unit).is_some());
+    }
+}
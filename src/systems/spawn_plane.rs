@@ -17,71 +17,84 @@ pub struct Plane {
     pub x_size: usize,
     pub y_size: usize,
 }
+
+/// One square of the board. Buildings reserve the cells under them while they
+/// are built and block them for pathing once finished.
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Cell {
     pub height: f32,
+    pub occupant: Option<Entity>,
+    pub blocked: bool,
+}
+
+/// Lays out an empty, flat board. Runs without rendering; `plane_setup` adds
+/// the visuals.
+pub fn setup_board(mut game: ResMut<Game>) {
+    game.board = (0..BOARD_SIZE_J as usize)
+        .map(|_| {
+            (0..BOARD_SIZE_I as usize)
+                .map(|_| Cell::default())
+                .collect()
+        })
+        .collect();
 }
 
 pub fn plane_setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    mut game: ResMut<Game>,
+    game: Res<Game>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
     // spawn the game board
     let cell_scene = asset_server.load("block_3.gltf#Scene0");
-    game.board = (0..BOARD_SIZE_J as usize)
-        .map(|j| {
-            (0..BOARD_SIZE_I as usize)
-                .map(|i| {
-                    let height = 0.0;
-                    commands
-                        .spawn(
-                            // HookedSceneBundle {
-                            //     scene: SceneBundle {
-                            //         transform: Transform::from_xyz(
-                            //             // Probably should be half block
-                            //             (i as f32 * BLOCK_SIZE / 2.) as f32,
-                            //             height,
-                            //             (j as f32 * BLOCK_SIZE / 2.) as f32,
-                            //         ),
-                            //         scene: cell_scene.clone(),
-                            //         visibility: Visibility { is_visible: true },
-                            //         ..default()
-                            //     },
-                            //     hook: SceneHook::new(|entity, commands| {
-                            //         match entity.get::<Name>().map(|t| t.as_str()) {
-                            //             Some("ground") => commands.insert(Ground),
-                            //             // Some("GltfNode2") => commands.insert(Ground),
-                            //             // Some("Entity (4)") => commands.insert(Ground),
-                            //             // Some("Pbr Mesh (11)") => commands.insert(Ground),
-                            //             _ => commands.insert(Ground),
-                            //         };
-                            //     }),
-                            // },
-                            (
-                                SceneBundle {
-                                    transform: Transform::from_xyz(
-                                        // Probably should be half block
-                                        (i as f32 * BLOCK_SIZE / 2.) as f32,
-                                        height,
-                                        (j as f32 * BLOCK_SIZE / 2.) as f32,
-                                    ),
-                                    scene: cell_scene.clone(),
-                                    visibility: Visibility { is_visible: true },
-                                    ..default()
-                                },
-                                // RayReflector,
+    for (j, row) in game.board.iter().enumerate() {
+        for (i, cell) in row.iter().enumerate() {
+            let height = cell.height;
+            commands
+                .spawn(
+                    // HookedSceneBundle {
+                    //     scene: SceneBundle {
+                    //         transform: Transform::from_xyz(
+                    //             // Probably should be half block
+                    //             (i as f32 * BLOCK_SIZE / 2.) as f32,
+                    //             height,
+                    //             (j as f32 * BLOCK_SIZE / 2.) as f32,
+                    //         ),
+                    //         scene: cell_scene.clone(),
+                    //         visibility: Visibility { is_visible: true },
+                    //         ..default()
+                    //     },
+                    //     hook: SceneHook::new(|entity, commands| {
+                    //         match entity.get::<Name>().map(|t| t.as_str()) {
+                    //             Some("ground") => commands.insert(Ground),
+                    //             // Some("GltfNode2") => commands.insert(Ground),
+                    //             // Some("Entity (4)") => commands.insert(Ground),
+                    //             // Some("Pbr Mesh (11)") => commands.insert(Ground),
+                    //             _ => commands.insert(Ground),
+                    //         };
+                    //     }),
+                    // },
+                    (
+                        SceneBundle {
+                            transform: Transform::from_xyz(
+                                // Probably should be half block
+                                (i as f32 * BLOCK_SIZE / 2.) as f32,
+                                height,
+                                (j as f32 * BLOCK_SIZE / 2.) as f32,
                             ),
-                        )
-                        .insert(RigidBody::Fixed)
-                        .insert(Name::new(format!("plane-{}-{}", i, j)))
-                        .insert(RaycastMesh::<RayReflector>::default()); // Make this mesh ray cast-able;
-                    Cell { height }
-                })
-                .collect()
-        })
-        .collect();
+                            scene: cell_scene.clone(),
+                            visibility: Visibility { is_visible: true },
+                            ..default()
+                        },
+                        // RayReflector,
+                    ),
+                )
+                .insert(RigidBody::Fixed)
+                .insert(Name::new(format!("plane-{}-{}", i, j)))
+                .insert(RaycastMesh::<RayReflector>::default()); // Make this mesh ray cast-able;
+        }
+    }
 
     pub const MAP_SIZE: f32 = 100.;
     pub const HALF_MAP_SIZE: f32 = MAP_SIZE / 2.;
@@ -1,113 +1,218 @@
+use std::f32::consts::{PI, TAU};
+
 use bevy::prelude::*;
-use bevy_iso3d_rts_cursor_plugin::{Cursor, Pickable};
-use bevy_rapier3d::prelude::{Collider, Damping, Dominance, LockedAxes, Restitution, RigidBody};
+use bevy_iso3d_rts_cursor_plugin::Pickable;
+use rand::Rng;
 
 use crate::{
-    components::mechanics::{MovementSpeed, RotationSpeed, Unit},
+    components::{
+        buildings::Building,
+        effects::{Easing, Tween, TweenProperty, Tweens},
+        mechanics::{Team, Unit, UnitType},
+        orders::Order,
+        vision::Fogged,
+    },
     constants::{
         constants::GROUND_LEVEL,
-        units::{self, SOCIAL_DISTANCE},
+        effects::{SPAWN_GROW_DURATION, SPAWN_START_SCALE},
+        units::SHIP_SCALE,
     },
-    Game,
 };
 
-pub fn spawn_unit(
-    mut commands: Commands,
-    buttons: Res<Input<MouseButton>>,
-    cursor: Res<Cursor>,
-    asset_server: Res<AssetServer>,
-) {
-    let number_of_units_to_spawn = 500;
-
-    if buttons.just_pressed(MouseButton::Right) {
-        println!("Spawning unit.");
-        let scale = 2.;
-        spawn_units_in_grid(
-            &spawn_tank,
-            number_of_units_to_spawn,
-            &mut commands,
-            &asset_server,
-            cursor.location.xyz.x,
-            cursor.location.xyz.z,
-            scale,
-        )
+/// How a batch of spawned units is arranged around the spawn point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpawnLayout {
+    #[default]
+    Grid,
+    Circle,
+    Scatter,
+}
+
+impl SpawnLayout {
+    pub const ALL: [SpawnLayout; 3] =
+        [SpawnLayout::Grid, SpawnLayout::Circle, SpawnLayout::Scatter];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SpawnLayout::Grid => "Grid",
+            SpawnLayout::Circle => "Circle",
+            SpawnLayout::Scatter => "Scatter",
+        }
+    }
+}
+
+/// Ground positions for `count` units centred on `center`, at least `spacing`
+/// apart in the grid and circle layouts. Scatter picks random points in a disc
+/// big enough to hold them all.
+pub fn formation_positions(
+    layout: SpawnLayout,
+    count: usize,
+    center: Vec3,
+    spacing: f32,
+) -> Vec<Vec3> {
+    let center = Vec3::new(center.x, GROUND_LEVEL, center.z);
+    match layout {
+        SpawnLayout::Grid => {
+            let side = (count as f32).sqrt().ceil() as usize;
+            let offset = (side.max(1) - 1) as f32 * spacing / 2.;
+            (0..count)
+                .map(|index| {
+                    let (i, j) = (index % side, index / side);
+                    center + Vec3::new(i as f32 * spacing - offset, 0., j as f32 * spacing - offset)
+                })
+                .collect()
+        }
+        SpawnLayout::Circle => {
+            // A single unit sits on the centre; more are spaced around the rim
+            // so that neighbours are `spacing` apart.
+            let radius = if count > 1 {
+                spacing / (2. * (PI / count as f32).sin())
+            } else {
+                0.
+            };
+            (0..count)
+                .map(|index| {
+                    let angle = index as f32 / count as f32 * TAU;
+                    center + Vec3::new(angle.cos(), 0., angle.sin()) * radius
+                })
+                .collect()
+        }
+        SpawnLayout::Scatter => {
+            let radius = spacing * (count as f32).sqrt();
+            let mut rng = rand::thread_rng();
+            (0..count)
+                .map(|_| {
+                    // The square root keeps points evenly spread over the disc.
+                    let distance = radius * rng.gen::<f32>().sqrt();
+                    let angle = rng.gen::<f32>() * TAU;
+                    center + Vec3::new(angle.cos(), 0., angle.sin()) * distance
+                })
+                .collect()
+        }
     }
 }
 
-fn spawn_tank(
+/// Spawns an idle unit of `unit_type` for `team`. Health, speed and weapons
+/// come from its unit definition; `decorate_units` gives it a model.
+pub fn spawn_unit_at(
     commands: &mut Commands,
-    asset_server: &Res<AssetServer>,
-    x: f32,
-    z: f32,
-    scale: f32,
+    unit_type: &str,
+    team: Team,
+    position: Vec3,
 ) -> Entity {
-    let entity_id = &commands
-        .spawn(SceneBundle {
-            transform: Transform {
-                translation: Vec3::new(x, GROUND_LEVEL, z),
-                scale: Vec3::new(scale, scale, scale),
-                ..default()
+    let unit = commands
+        .spawn((
+            TransformBundle::from_transform(Transform::from_translation(position)),
+            Unit,
+            team,
+            UnitType(unit_type.to_string()),
+            Order::Idle,
+            // RigidBody::Dynamic,
+            // LockedAxes::TRANSLATION_LOCKED_Y | LockedAxes::ROTATION_LOCKED_Y,
+            // Collider::ball(0.5),
+            // Restitution::coefficient(0.01),
+            // Damping { linear_damping: 15.5, angular_damping: 1.0 },
+        ))
+        .id();
+    commands
+        .entity(unit)
+        .insert(Name::new(format!("{}-{:?}", unit_type, unit)));
+    unit
+}
+
+/// Gives units the ship model, a light and cursor picking, and grows them into
+/// view. Kept apart from spawning so units also work without rendering.
+/// Units already in the fog start hidden and unpickable.
+pub fn decorate_units(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut units: Query<
+        (Entity, &mut Transform, Option<&Fogged>),
+        (Added<UnitType>, Without<Building>, Without<Handle<Scene>>),
+    >,
+) {
+    for (unit, mut transform, fogged) in &mut units {
+        transform.scale = Vec3::splat(SHIP_SCALE * SPAWN_START_SCALE);
+        let grow = Tween::new(
+            "spawn",
+            TweenProperty::Scale {
+                from: transform.scale,
+                to: Vec3::splat(SHIP_SCALE),
             },
-            scene: asset_server.load("ship.gltf#Scene0"),
-            ..default()
-        })
-        .with_children(|children| {
-            children
-                .spawn(PointLightBundle {
-                    point_light: PointLight {
-                        color: Color::rgb(1.0, 1.0, 0.0),
-                        intensity: 50.0,
-                        range: 45.0,
-                        shadows_enabled: false,
-                        ..default()
+            SPAWN_GROW_DURATION,
+        )
+        .with_easing(Easing::QuadOut);
+        commands
+            .entity(unit)
+            .insert((
+                asset_server.load::<Scene, _>("ship.gltf#Scene0"),
+                VisibilityBundle {
+                    visibility: Visibility {
+                        is_visible: fogged.is_none(),
                     },
-                    transform: Transform::from_xyz(0.0, 2.0, 0.0),
                     ..default()
-                })
-                .insert(Name::new("ShipLight"));
-        })
-        .insert(Unit)
-        .insert(Pickable)
-        // .insert(RigidBody::Dynamic)
-        // .insert(LockedAxes::TRANSLATION_LOCKED_Y | LockedAxes::ROTATION_LOCKED_Y)
-        // .insert(Collider::ball(0.5))
-        // .insert(Restitution::coefficient(0.01))
-        // .insert(Damping {
-        //     linear_damping: 15.5,
-        //     angular_damping: 1.0,
-        // })
-        .insert(MovementSpeed { value: 2. })
-        .insert(RotationSpeed { value: 150. })
-        .id();
-
-    commands
-        .entity(*entity_id)
-        .insert(Name::new(format!("Ship-{:?}", entity_id)))
-        .id()
+                },
+                Tweens::new(grow),
+            ))
+            .with_children(|children| {
+                children.spawn((
+                    PointLightBundle {
+                        point_light: PointLight {
+                            color: Color::rgb(1.0, 1.0, 0.0),
+                            intensity: 50.0,
+                            range: 45.0,
+                            shadows_enabled: false,
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(0.0, 2.0, 0.0),
+                        ..default()
+                    },
+                    Name::new("ShipLight"),
+                ));
+            });
+        if fogged.is_none() {
+            commands.entity(unit).insert(Pickable);
+        }
+    }
 }
 
-fn spawn_units_in_grid(
-    spawn_fn: &dyn Fn(&mut Commands, &Res<AssetServer>, f32, f32, f32) -> Entity,
-    units_to_spawn: i32,
-    commands: &mut Commands,
-    asset_server: &Res<AssetServer>,
-    x: f32,
-    z: f32,
-    scale: f32,
-) -> () {
-    let num_of_iters = (units_to_spawn as f64).log2().ceil() as i32;
-    let group_offset = num_of_iters as f32 * scale - scale;
-
-    for i in 0..num_of_iters {
-        for j in 0..num_of_iters {
-            let current_unit_index = i * num_of_iters + j;
-            if current_unit_index >= units_to_spawn {
-                break;
-            }
-            let adjusted_x = x + i as f32 + SOCIAL_DISTANCE + i as f32 * scale - group_offset;
-            let adjusted_z = z + j as f32 + SOCIAL_DISTANCE + j as f32 * scale - group_offset;
-            // println!("num: {:?}, x: {:?}, z: {:?}", i + i, z as i32, x as i32);
-            spawn_fn(commands, &asset_server, adjusted_x, adjusted_z, scale);
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{formation_positions, SpawnLayout};
+    use crate::constants::constants::GROUND_LEVEL;
+
+    #[test]
+    fn grid_is_centred_and_spaced() {
+        let center = Vec3::new(10., GROUND_LEVEL, 10.);
+
+        let positions = formation_positions(SpawnLayout::Grid, 4, center, 2.);
+
+        assert_eq!(
+            positions,
+            [
+                Vec3::new(9., GROUND_LEVEL, 9.),
+                Vec3::new(11., GROUND_LEVEL, 9.),
+                Vec3::new(9., GROUND_LEVEL, 11.),
+                Vec3::new(11., GROUND_LEVEL, 11.),
+            ]
+        );
+    }
+
+    #[test]
+    fn circle_neighbours_are_at_least_spacing_apart() {
+        let positions = formation_positions(SpawnLayout::Circle, 12, Vec3::ZERO, 3.);
+
+        for pair in positions.windows(2) {
+            assert!(pair[0].distance(pair[1]) >= 3. - 0.001);
+        }
+    }
+
+    #[test]
+    fn every_layout_spawns_the_requested_count() {
+        for layout in SpawnLayout::ALL {
+            assert_eq!(formation_positions(layout, 37, Vec3::ZERO, 3.).len(), 37);
         }
     }
 }
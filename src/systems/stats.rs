@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        mechanics::{Team, Unit},
+        stats::{Aura, StatEffect, StatEffects},
+    },
+    constants::units::AURA_LINGER,
+    util::ground_distance,
+};
+
+/// Runs down every unit's effects and drops the ones that are over.
+pub fn stat_effects_system(time: Res<Time>, mut units: Query<&mut StatEffects>) {
+    for mut effects in &mut units {
+        if !effects.effects.is_empty() {
+            effects.tick(time.delta());
+        }
+    }
+}
+
+/// Keeps topping up aura effects on friendly units in range, so they wear off
+/// shortly after a unit leaves the aura or its source dies.
+pub fn aura_system(
+    auras: Query<(&Aura, &Transform, Option<&Team>), With<Unit>>,
+    mut units: Query<(&Transform, Option<&Team>, &mut StatEffects), With<Unit>>,
+) {
+    let auras: Vec<(&Aura, Vec3, Team)> = auras
+        .iter()
+        .map(|(aura, transform, team)| {
+            (
+                aura,
+                transform.translation,
+                team.copied().unwrap_or_default(),
+            )
+        })
+        .collect();
+    if auras.is_empty() {
+        return;
+    }
+
+    for (transform, team, mut effects) in &mut units {
+        let team = team.copied().unwrap_or_default();
+        for (aura, position, aura_team) in &auras {
+            if *aura_team != team || ground_distance(*position, transform.translation) > aura.radius
+            {
+                continue;
+            }
+            effects.add(StatEffect::new(
+                &aura.name,
+                aura.stat,
+                aura.change,
+                AURA_LINGER,
+            ));
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        mechanics::{Destination, Detour, Rotating, StuckDetection},
+        orders::Order,
+    },
+    constants::{
+        plane::GAME_BOUNDS,
+        units::{DETOUR_DISTANCE, NUDGE_DISTANCE},
+    },
+    util::{ground_distance, keep_in_bounds},
+};
+
+/// Sent when a unit gives up on an order it could not get any closer to
+/// finishing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderFailed {
+    pub unit: Entity,
+    pub order: Order,
+}
+
+/// What a stuck unit tries next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StuckRemedy {
+    /// Head for a waypoint off to the side, then try again.
+    Repath,
+    /// Shove the unit sideways.
+    Nudge,
+    GiveUp,
+}
+
+impl StuckDetection {
+    pub fn remedy(&self) -> StuckRemedy {
+        if self.attempts < self.repaths {
+            StuckRemedy::Repath
+        } else if self.attempts < self.repaths.saturating_add(self.nudges) {
+            StuckRemedy::Nudge
+        } else {
+            StuckRemedy::GiveUp
+        }
+    }
+}
+
+/// Tracks moving units' progress toward their destination and gets stuck ones
+/// going again: first by detouring, then by nudging them, and finally by
+/// dropping the order and sending `OrderFailed`.
+pub fn stuck_detection_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut moving: Query<(
+        Entity,
+        &mut Transform,
+        &Destination,
+        Option<&mut StuckDetection>,
+        Option<&mut Order>,
+        Option<&Detour>,
+    )>,
+    mut resting: Query<&mut StuckDetection, (Without<Destination>, Without<Detour>)>,
+    mut failures: EventWriter<OrderFailed>,
+) {
+    for mut detection in &mut resting {
+        if detection.attempts > 0 || detection.best_distance < f32::MAX {
+            detection.reset();
+        }
+    }
+
+    for (unit, mut transform, destination, detection, order, detour) in &mut moving {
+        let Some(mut detection) = detection else {
+            commands.entity(unit).insert(StuckDetection::default());
+            continue;
+        };
+        // Patrols turning around and new orders start with a clean slate.
+        if order.as_ref().map_or(false, |order| order.is_changed()) {
+            detection.reset();
+        }
+        let position = transform.translation;
+        let distance = ground_distance(position, destination.0);
+        if !detection.track(destination.0, distance, time.delta_seconds()) {
+            continue;
+        }
+
+        let heading = (destination.0 - position) * Vec3::new(1., 0., 1.);
+        let heading = heading.normalize_or_zero();
+        // Alternate sides, in case the first one was no better.
+        let side = if detection.attempts % 2 == 0 { 1. } else { -1. };
+        let sideways = Vec3::new(-heading.z, 0., heading.x) * side;
+        // Attacks and gathers re-aim every frame, so a detour would not last.
+        let can_detour = matches!(
+            order.as_deref(),
+            None | Some(Order::Move(_))
+                | Some(Order::Patrol { .. })
+                | Some(Order::AttackMove { engaging: None, .. })
+        );
+        let remedy = match detection.remedy() {
+            StuckRemedy::Repath if !can_detour => StuckRemedy::Nudge,
+            remedy => remedy,
+        };
+        detection.attempts += 1;
+
+        match remedy {
+            StuckRemedy::Repath => {
+                let waypoint = keep_in_bounds(
+                    GAME_BOUNDS,
+                    position + (sideways + heading * 0.5) * DETOUR_DISTANCE,
+                    2.,
+                );
+                commands
+                    .entity(unit)
+                    .insert(Destination(waypoint))
+                    .insert(Rotating)
+                    .insert(Detour {
+                        resume: detour.map_or(destination.0, |detour| detour.resume),
+                    });
+            }
+            StuckRemedy::Nudge => {
+                let nudged = keep_in_bounds(GAME_BOUNDS, position + sideways * NUDGE_DISTANCE, 2.);
+                transform.translation = Vec3::new(nudged.x, position.y, nudged.z);
+            }
+            StuckRemedy::GiveUp => {
+                let goal = detour.map_or(destination.0, |detour| detour.resume);
+                let failed = match order {
+                    Some(mut order) => std::mem::replace(&mut *order, Order::Idle),
+                    None => Order::Move(goal),
+                };
+                commands
+                    .entity(unit)
+                    .remove::<Destination>()
+                    .remove::<Rotating>()
+                    .remove::<Detour>();
+                failures.send(OrderFailed {
+                    unit,
+                    order: failed,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::StuckRemedy;
+    use crate::components::mechanics::StuckDetection;
+
+    #[test]
+    fn a_unit_that_stops_getting_closer_is_stuck_after_the_timeout() {
+        let mut detection = StuckDetection::new(1., 0.5, 1, 1);
+        let target = Vec3::new(10., 0., 0.);
+
+        assert!(!detection.track(target, 8., 0.5));
+        assert!(!detection.track(target, 7., 0.5));
+        assert!(!detection.track(target, 6.8, 0.5));
+        assert!(detection.track(target, 6.8, 0.5));
+        // Being sent somewhere else starts over.
+        assert!(!detection.track(Vec3::ZERO, 6.8, 5.));
+    }
+
+    #[test]
+    fn remedies_escalate_from_detours_to_nudges_to_giving_up() {
+        let mut detection = StuckDetection::new(1., 0.5, 1, 2);
+        let mut remedies = Vec::new();
+        for _ in 0..4 {
+            remedies.push(detection.remedy());
+            detection.attempts += 1;
+        }
+
+        assert_eq!(
+            remedies,
+            [
+                StuckRemedy::Repath,
+                StuckRemedy::Nudge,
+                StuckRemedy::Nudge,
+                StuckRemedy::GiveUp
+            ]
+        );
+    }
+}
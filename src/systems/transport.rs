@@ -0,0 +1,168 @@
+use std::{collections::HashSet, f32::consts::TAU};
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    components::{
+        mechanics::{
+            Destination, Detour, KinematicVelocity, MovementSpeed, PhysicsMovement, Rotating, Team,
+            Unit,
+        },
+        orders::Order,
+        transport::{Loaded, Transport},
+    },
+    constants::{
+        constants::GROUND_LEVEL,
+        plane::GAME_BOUNDS,
+        units::{LOAD_RADIUS, UNLOAD_SPACING},
+    },
+    systems::{
+        commands::{CommandEvent, UnitCommand},
+        movement::physics_body,
+    },
+    util::{ground_distance, keep_in_bounds},
+};
+
+/// Selected transports and garrisonable buildings take in the nearest
+/// friendly mobile units around them, as many as they have room for. Units
+/// carrying cargo of their own stay out.
+pub fn load_units_system(
+    mut commands: Commands,
+    mut events: EventReader<CommandEvent>,
+    mut units: Query<
+        (
+            Entity,
+            &Transform,
+            Option<&Team>,
+            Option<&mut Transport>,
+            Option<&MovementSpeed>,
+        ),
+        With<Unit>,
+    >,
+) {
+    let mut taken = HashSet::new();
+    for event in events.iter() {
+        if event.command != UnitCommand::Load {
+            continue;
+        }
+        for carrier in &event.units {
+            if taken.contains(carrier) {
+                continue;
+            }
+            let Ok((_, transform, team, Some(transport), _)) = units.get(*carrier) else {
+                continue;
+            };
+            let position = transform.translation;
+            let team = team.copied().unwrap_or_default();
+            let room = transport.room();
+
+            let mut passengers: Vec<(Entity, f32)> = units
+                .iter()
+                .filter(
+                    |(passenger, _, passenger_team, passenger_transport, speed)| {
+                        passenger != carrier
+                            && !taken.contains(passenger)
+                            && speed.is_some()
+                            && passenger_team.copied().unwrap_or_default() == team
+                            && passenger_transport.map_or(true, |t| t.cargo.is_empty())
+                    },
+                )
+                .map(|(passenger, passenger_transform, ..)| {
+                    (
+                        passenger,
+                        ground_distance(position, passenger_transform.translation),
+                    )
+                })
+                .filter(|(_, distance)| *distance <= LOAD_RADIUS)
+                .collect();
+            passengers.sort_by(|a, b| a.1.total_cmp(&b.1));
+            passengers.truncate(room);
+            if passengers.is_empty() {
+                continue;
+            }
+
+            // The carrier itself cannot be picked up by anyone else this frame.
+            taken.insert(*carrier);
+            let Ok((.., Some(mut transport), _)) = units.get_mut(*carrier) else {
+                continue;
+            };
+            for (passenger, _) in passengers {
+                taken.insert(passenger);
+                transport.cargo.push(passenger);
+                commands
+                    .entity(passenger)
+                    .insert(Loaded { carrier: *carrier })
+                    .remove::<Unit>()
+                    .remove::<Order>()
+                    .remove::<Destination>()
+                    .remove::<Rotating>()
+                    .remove::<Detour>()
+                    .remove::<KinematicVelocity>()
+                    .remove::<RigidBody>()
+                    .remove::<Collider>();
+            }
+        }
+    }
+}
+
+/// Carriers unloading that have reached their spot, or cannot move, set their
+/// cargo down in a ring around themselves. Cargo let out away from the spot,
+/// as from a building, walks the rest of the way there.
+pub fn unload_units_system(
+    mut commands: Commands,
+    mut carriers: Query<
+        (&Transform, &mut Order, &mut Transport),
+        (Without<Destination>, Without<Loaded>),
+    >,
+    mut cargo: Query<(&mut Transform, Option<&PhysicsMovement>), With<Loaded>>,
+) {
+    for (transform, mut order, mut transport) in &mut carriers {
+        let Order::Unload(point) = *order else {
+            continue;
+        };
+        *order = Order::Idle;
+
+        let position = transform.translation;
+        let walk = ground_distance(position, point) > UNLOAD_SPACING * 2.;
+        let count = transport.cargo.len();
+        for (index, passenger) in transport.cargo.drain(..).enumerate() {
+            let angle = TAU * index as f32 / count as f32;
+            let offset = Vec3::new(angle.cos(), 0., angle.sin()) * UNLOAD_SPACING;
+            let Ok((mut passenger_transform, physics)) = cargo.get_mut(passenger) else {
+                continue;
+            };
+            let mut spot = keep_in_bounds(GAME_BOUNDS, position + offset, 2.);
+            spot.y = GROUND_LEVEL;
+            passenger_transform.translation = spot;
+
+            let mut passenger = commands.entity(passenger);
+            passenger.remove::<Loaded>().insert(Unit);
+            if physics.is_some() {
+                passenger.insert(physics_body());
+            }
+            if walk {
+                let destination = keep_in_bounds(GAME_BOUNDS, point + offset, 2.);
+                passenger
+                    .insert(Destination(destination))
+                    .insert(Rotating)
+                    .insert(Order::Move(destination));
+            } else {
+                passenger.insert(Order::Idle);
+            }
+        }
+    }
+}
+
+/// Cargo goes down with its carrier.
+pub fn lost_cargo_system(
+    mut commands: Commands,
+    cargo: Query<(Entity, &Loaded)>,
+    carriers: Query<(), With<Transport>>,
+) {
+    for (passenger, loaded) in &cargo {
+        if !carriers.contains(loaded.carrier) {
+            commands.entity(passenger).despawn_recursive();
+        }
+    }
+}
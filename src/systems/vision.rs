@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        mechanics::{Team, Unit},
+        stats::{effective, Stat, StatEffects},
+        vision::{FogOfWar, Fogged, Obstacle, SightRange},
+    },
+    constants::vision::DEFAULT_SIGHT_RANGE,
+    systems::line_of_sight::LineOfSight,
+};
+
+pub fn reset_fog(mut fog: ResMut<FogOfWar>) {
+    *fog = FogOfWar::default();
+}
+
+/// The fog cells a unit saw from the fog cell it stood in, with the sight
+/// range it had there.
+struct UnitSight {
+    cell: Option<(usize, usize)>,
+    range: f32,
+    cells: Vec<(usize, usize)>,
+}
+
+/// What units saw, kept until they move to another fog cell or something that
+/// blocks sight changes.
+#[derive(Default)]
+pub struct VisionCache {
+    sights: HashMap<Entity, UnitSight>,
+    blocked_cells: Vec<bool>,
+    obstacles: Vec<(Vec3, Obstacle)>,
+}
+
+/// Recomputes what every team sees from where its units stand, out to their
+/// sight range and not past what blocks their line of sight. What a team saw
+/// before stays explored.
+pub fn update_vision_system(
+    mut fog: ResMut<FogOfWar>,
+    mut cache: Local<VisionCache>,
+    line_of_sight: LineOfSight,
+    units: Query<
+        (
+            Entity,
+            &Transform,
+            Option<&Team>,
+            Option<&SightRange>,
+            Option<&StatEffects>,
+        ),
+        With<Unit>,
+    >,
+) {
+    for vision in fog.teams.values_mut() {
+        vision.fade();
+    }
+
+    let cache = &mut *cache;
+    let blocked_cells = line_of_sight.blocked_cells();
+    let obstacles = line_of_sight.obstacles();
+    if cache.blocked_cells != blocked_cells || cache.obstacles != obstacles {
+        cache.sights.clear();
+        cache.blocked_cells = blocked_cells;
+        cache.obstacles = obstacles;
+    }
+    cache.sights.retain(|unit, _| units.contains(*unit));
+
+    for (unit, transform, team, sight, effects) in &units {
+        let position = transform.translation;
+        let range = effective(
+            effects,
+            Stat::SightRange,
+            sight.map_or(DEFAULT_SIGHT_RANGE, |sight| sight.value),
+        );
+        let range = line_of_sight.sight_range(range, position);
+        let vision = fog
+            .teams
+            .entry(team.copied().unwrap_or_default())
+            .or_default();
+        let cell = vision.cell_index(position);
+
+        let stale = cache
+            .sights
+            .get(&unit)
+            .map_or(true, |sight| sight.cell != cell || sight.range != range);
+        if stale {
+            let cells = vision.visible_cells(position, range, |cell| {
+                line_of_sight.can_see_past(&cache.obstacles, position, cell)
+            });
+            cache.sights.insert(unit, UnitSight { cell, range, cells });
+        }
+        vision.show(&cache.sights[&unit].cells);
+    }
+}
+
+/// Tags enemy units the player cannot see with `Fogged`, and untags them when
+/// they come back into sight.
+pub fn mark_fogged_system(
+    mut commands: Commands,
+    fog: Res<FogOfWar>,
+    units: Query<(Entity, &Transform, Option<&Team>, Option<&Fogged>), With<Unit>>,
+) {
+    for (unit, transform, team, fogged) in &units {
+        let hidden = team.copied().unwrap_or_default() != Team::PLAYER
+            && !fog.is_visible(Team::PLAYER, transform.translation);
+        match (hidden, fogged.is_some()) {
+            (true, false) => {
+                commands.entity(unit).insert(Fogged);
+            }
+            (false, true) => {
+                commands.entity(unit).remove::<Fogged>();
+            }
+            _ => {}
+        }
+    }
+}
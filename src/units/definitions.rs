@@ -0,0 +1,241 @@
+use std::{collections::HashMap, fs};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::stats::{deserialize_timed_stat, Stat, StatChange};
+
+/// Stats and abilities of every unit type, loaded from the units file.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnitDefinitions {
+    pub units: HashMap<String, UnitDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnitDefinition {
+    pub health: f32,
+    pub speed: f32,
+    pub rotation_speed: f32,
+    #[serde(default)]
+    pub weapon: Option<WeaponDefinition>,
+    #[serde(default)]
+    pub abilities: Vec<AbilityDefinition>,
+    #[serde(default)]
+    pub harvester: Option<HarvesterDefinition>,
+    /// How far it sees through the fog of war, if not the default.
+    #[serde(default)]
+    pub sight_range: Option<f32>,
+    #[serde(default)]
+    pub movement: MovementMode,
+    /// When its units count as stuck, if not the defaults.
+    #[serde(default)]
+    pub stuck: Option<StuckDefinition>,
+    /// How many units it can carry, if it is a transport.
+    #[serde(default)]
+    pub transport: Option<TransportDefinition>,
+    /// A stat change it lends friendly units around it, itself included.
+    #[serde(default)]
+    pub aura: Option<AuraDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransportDefinition {
+    pub capacity: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuraDefinition {
+    pub name: String,
+    pub radius: f32,
+    #[serde(deserialize_with = "deserialize_timed_stat")]
+    pub stat: Stat,
+    pub change: StatChange,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StuckDefinition {
+    /// Seconds without progress before the unit counts as stuck.
+    pub timeout: f32,
+    /// How much closer it must get to count as progress.
+    pub min_progress: f32,
+    /// Detours to try before nudging.
+    pub repaths: u8,
+    /// Nudges to try before giving up on the order.
+    pub nudges: u8,
+}
+
+/// How units of a type move. Kinematic units are placed along their path and
+/// nudged apart; physics units are Rapier bodies that are steered by velocity
+/// and collide with each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MovementMode {
+    #[default]
+    Kinematic,
+    Physics,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeaponDefinition {
+    pub damage: f32,
+    pub range: f32,
+    /// Seconds between shots.
+    pub cooldown: f32,
+    /// Makes shots burst, if set.
+    #[serde(default)]
+    pub splash: Option<SplashDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplashDefinition {
+    pub radius: f32,
+    /// Share of the damage lost by the edge of the burst.
+    pub falloff: f32,
+    /// How hard physics bodies at the center are pushed away.
+    #[serde(default)]
+    pub impulse: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarvesterDefinition {
+    /// Resources gathered per second.
+    pub gather_rate: f32,
+    pub capacity: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbilityDefinition {
+    pub name: String,
+    pub tooltip: String,
+    /// Seconds before the ability can be used again.
+    pub cooldown: f32,
+    pub effect: AbilityEffect,
+}
+
+/// What using an ability does to the unit using it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AbilityEffect {
+    Heal(f32),
+    /// Changes one of the unit's stats for `duration` seconds. Health cannot
+    /// be changed this way; `Heal` restores it.
+    Modify {
+        #[serde(deserialize_with = "deserialize_timed_stat")]
+        stat: Stat,
+        change: StatChange,
+        duration: f32,
+    },
+}
+
+impl UnitDefinitions {
+    pub fn from_ron(source: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    pub fn load_or_default(path: &str) -> Self {
+        match fs::read_to_string(path).map(|source| Self::from_ron(&source)) {
+            Ok(Ok(definitions)) => definitions,
+            Ok(Err(error)) => {
+                warn!(
+                    "Could not parse {}: {}. No unit types defined.",
+                    path, error
+                );
+                Self::default()
+            }
+            Err(error) => {
+                warn!("Could not read {}: {}. No unit types defined.", path, error);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn get(&self, unit_type: &str) -> Option<&UnitDefinition> {
+        self.units.get(unit_type)
+    }
+
+    pub fn ability(&self, unit_type: &str, name: &str) -> Option<&AbilityDefinition> {
+        self.get(unit_type)?
+            .abilities
+            .iter()
+            .find(|ability| ability.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AbilityEffect, MovementMode, UnitDefinitions};
+    use crate::components::stats::{Stat, StatChange};
+
+    #[test]
+    fn units_file_parses() {
+        let definitions =
+            UnitDefinitions::from_ron(include_str!("../../assets/data/units.ron")).unwrap();
+
+        let ship = definitions.get("ship").unwrap();
+        assert!(ship.weapon.is_some() && ship.harvester.is_some());
+        assert!(ship.transport.is_some());
+        assert_eq!(
+            definitions.ability("ship", "Repair").unwrap().effect,
+            AbilityEffect::Heal(40.)
+        );
+    }
+
+    #[test]
+    fn weapon_and_abilities_are_optional() {
+        let definitions = UnitDefinitions::from_ron(
+            r#"(units: {"crate": (health: 10.0, speed: 0.0, rotation_speed: 0.0)})"#,
+        )
+        .unwrap();
+
+        let definition = definitions.get("crate").unwrap();
+        assert!(definition.weapon.is_none() && definition.abilities.is_empty());
+        assert_eq!(definition.movement, MovementMode::Kinematic);
+    }
+
+    #[test]
+    fn timed_abilities_and_auras_parse() {
+        let definitions = UnitDefinitions::from_ron(
+            r#"(units: {"flagship": (
+                health: 200.0,
+                speed: 1.5,
+                rotation_speed: 90.0,
+                aura: Some((name: "Command", radius: 6.0, stat: Damage, change: Percent(25.0))),
+                abilities: [(
+                    name: "Overdrive",
+                    tooltip: "",
+                    cooldown: 30.0,
+                    effect: Modify(stat: Speed, change: Multiply(2.0), duration: 5.0),
+                )],
+            )})"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            definitions.ability("flagship", "Overdrive").unwrap().effect,
+            AbilityEffect::Modify {
+                stat: Stat::Speed,
+                change: StatChange::Multiply(2.),
+                duration: 5.
+            }
+        );
+        let aura = definitions.get("flagship").unwrap().aura.as_ref().unwrap();
+        assert_eq!(aura.change, StatChange::Percent(25.));
+    }
+
+    #[test]
+    fn timed_effects_on_health_are_refused() {
+        let definitions = UnitDefinitions::from_ron(
+            r#"(units: {"flagship": (
+                health: 200.0,
+                speed: 1.5,
+                rotation_speed: 90.0,
+                abilities: [(
+                    name: "Shield",
+                    tooltip: "",
+                    cooldown: 30.0,
+                    effect: Modify(stat: Health, change: Add(50.0), duration: 5.0),
+                )],
+            )})"#,
+        );
+
+        assert!(definitions.is_err());
+    }
+}
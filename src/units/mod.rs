@@ -1,3 +1,4 @@
+pub mod definitions;
 pub mod skelly;
 
 trait CurrentAnimation {
@@ -1,4 +1,4 @@
-use bevy::prelude::Vec3;
+use bevy::prelude::{Vec2, Vec3};
 use bevy_iso3d_rts_cursor_plugin::Bounds2D;
 
 // pub fn mean(numbers: Vec<f32>) -> f32 {
@@ -14,6 +14,11 @@ pub fn are_positions_near(v1: &Vec3, v2: &Vec3, sensitivity: f32) -> bool {
     v2.cmpgt(*v1 - sensitivity).all() && v2.cmplt(*v1 + sensitivity).all()
 }
 
+/// Distance between two points ignoring height.
+pub fn ground_distance(v1: Vec3, v2: Vec3) -> f32 {
+    Vec2::new(v1.x - v2.x, v1.z - v2.z).length()
+}
+
 pub fn keep_in_bounds(bounds: Bounds2D, mut pos: Vec3, padding: f32) -> Vec3 {
     if pos.x < bounds.min_x + padding {
         pos.x = bounds.min_x + padding